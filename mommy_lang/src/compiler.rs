@@ -1,32 +1,131 @@
-use std::collections::HashMap;
 use std::fs;
 use std::io::Write;
 
 use mommy_lib::alu;
+use mommy_lib::choice;
 use mommy_lib::conditions;
 use mommy_lib::constants;
 use mommy_lib::declaration;
+use mommy_lib::diagnostic::{self, Diagnostic};
+use mommy_lib::functions::{self, FunctionFrame};
 use mommy_lib::io;
 use mommy_lib::lang_enums::ScopeType;
-use mommy_lib::loops;
+use mommy_lib::lang_syntax::MommyLangSyntax;
+use mommy_lib::loops::{self, LoopFrame};
+use mommy_lib::lifetime;
 use mommy_lib::package;
+use mommy_lib::package_list;
+use mommy_lib::pick::{self, PickFrame};
+use mommy_lib::record;
 use mommy_lib::responses;
-use mommy_lib::syntax_lexer;
+use mommy_lib::symbol_table::{Symbol, SymbolTable};
+use mommy_lib::syntax_lexer::{self, Span, Token};
+use mommy_lib::text;
+use mommy_lib::type_checker;
+use mommy_lib::validate_syntax;
 use mommy_lib::shell_format::print_line;
 
 use crate::config::Config;
 
+/// Chores live outside of main, so their C is collected separately.
+#[derive(Default)]
+struct Chores {
+    current: Option<FunctionFrame>,
+    prototypes: Vec<String>,
+    definitions: Vec<String>,
+}
+
+/// Loops and picks that are still open, innermost last. They sit in the scope stack too, this is what they carry.
+#[derive(Default)]
+struct Blocks {
+    loops: Vec<LoopFrame>,
+    picks: Vec<PickFrame>,
+    loops_opened: usize, // Every loop gets its own exit label
+}
+
+impl Blocks {
+    /// Opens the loop's own room in the symbol table too, the body lives in there.
+    fn open_loop(&mut self, label: Option<String>, runs_first: bool, symbols: &mut SymbolTable) {
+        self.loops_opened += 1;
+        symbols.push_scope();
+        self.loops.push(LoopFrame::new(self.loops_opened, symbols.depth(), label, runs_first));
+    }
+
+    /// "satisfied" and "skip", with or without a loop name. A plain C break or continue when that reaches the right loop.
+    /// Every text declared in the rooms being left is freed first.
+    fn leave_loop(&mut self, tokens: &[Token], scope_stack: &[ScopeType], symbols: &SymbolTable, is_break: bool) -> Result<String, responses::MommyLangError> {
+        if tokens.len() > 2 {
+            return Err(responses::MommyLangError::SyntaxError);
+        }
+        let target = match tokens.get(1) {
+            Some(name) => self.loops.iter().rposition(|frame| frame.is_named(name.as_str())).ok_or(responses::MommyLangError::UnknownLoop)?,
+            None => self.loops.len().checked_sub(1).ok_or(responses::MommyLangError::UnexpectedSatisfied)?,
+        };
+
+        let innermost = target + 1 == self.loops.len();
+        let inside_pick = scope_stack.iter().rev()
+            .take_while(|scope| **scope != ScopeType::Loop)
+            .any(|scope| matches!(scope, ScopeType::Arm | ScopeType::Otherwise));
+
+        let frame = &mut self.loops[target];
+        let released = text::release(&symbols.texts_from(frame.depth()));
+        let jump = match (is_break, innermost) {
+            (true, true) if !inside_pick => loops::satisfied(),
+            (true, _) => loops::satisfied_from(frame),
+            (false, true) => loops::skip(), // C's continue goes through a switch just fine
+            (false, false) => loops::skip_from(frame),
+        };
+        Ok(format!("{}{}", released, jump))
+    }
+}
+
+/// Every mistake found so far, up to the limit. One more than that and mommy stops reading.
+struct Complaints {
+    diagnostics: Vec<Diagnostic>,
+    limit: usize,
+    stopped: bool,
+}
+
+impl Complaints {
+    fn new(limit: usize) -> Self {
+        Complaints { diagnostics: Vec::new(), limit, stopped: false }
+    }
+
+    /// Keeps the mistake. When there is no room left, says so once instead, and false means stop reading.
+    fn report(&mut self, diagnostic: Diagnostic) -> bool {
+        if self.stopped {
+            return false;
+        }
+        if self.diagnostics.len() >= self.limit {
+            self.diagnostics.push(Diagnostic::error(&responses::MommyLangError::TooManyErrors(self.limit)));
+            self.stopped = true;
+            return false;
+        }
+        self.diagnostics.push(diagnostic);
+        true
+    }
+}
+
 fn parse_line(
-    tokens: Vec<String>,
-    symbols: &mut HashMap<String, String>,
+    tokens: Vec<Token>,
+    symbols: &mut SymbolTable,
     scope_stack: &mut Vec<ScopeType>,
     includes: &mut Vec<String>,
+    globals: &mut Vec<String>,
+    chores: &mut Chores,
+    blocks: &mut Blocks,
 ) -> Result<String, responses::MommyLangError> {
     if tokens.is_empty() {
         return Ok(String::new());
     }
 
-    let command = mommy_lib::lang_syntax::MommyLangSyntax::from_str(&tokens[constants::IDX_SYNTAX_DEF_INITIATE]);
+    let command = mommy_lib::lang_syntax::MommyLangSyntax::from_word(tokens[constants::IDX_SYNTAX_DEF_INITIATE].as_str());
+    if scope_stack.last() == Some(&ScopeType::Pick) && // Nothing lives between "pick" and its first arm
+        !matches!(command, mommy_lib::lang_syntax::MommyLangSyntax::PickArm | mommy_lib::lang_syntax::MommyLangSyntax::PickOtherwise | mommy_lib::lang_syntax::MommyLangSyntax::LoopEnd) {
+        return Err(responses::MommyLangError::OutsideArm);
+    }
+    type_checker::check_statement(&command, &tokens, symbols)?; // Types first, C second
+    lifetime::check_statement(&command, &tokens, symbols)?;
 
     match command {
         // --- Variables ---
@@ -34,13 +133,30 @@ fn parse_line(
             declaration::create_variable(&tokens, symbols)
         }
         mommy_lib::lang_syntax::MommyLangSyntax::Assignment => declaration::replace(&tokens, symbols),
+        mommy_lib::lang_syntax::MommyLangSyntax::Constant => {
+            let c_code = declaration::create_constant(&tokens, symbols)?;
+            if scope_stack.is_empty() { // Top level constants are global, so chores can use them too
+                globals.push(c_code);
+                return Ok(String::new());
+            }
+            Ok(c_code)
+        }
         mommy_lib::lang_syntax::MommyLangSyntax::Array => declaration::create_array(&tokens, symbols),
         mommy_lib::lang_syntax::MommyLangSyntax::String => declaration::create_array(&tokens, symbols),
 
         // --- Dynamic Memory Allocation ---
         mommy_lib::lang_syntax::MommyLangSyntax::Malloc => declaration::allocate_heap(&tokens, symbols),
+        mommy_lib::lang_syntax::MommyLangSyntax::MallocResize => declaration::resize_heap(&tokens, symbols),
         mommy_lib::lang_syntax::MommyLangSyntax::FreeMalloc => declaration::deallocate_heap(&tokens, symbols),
 
+        // --- Type Conversion ---
+        mommy_lib::lang_syntax::MommyLangSyntax::Conversion => alu::convert(&tokens, symbols),
+
+        // --- Text ---
+        mommy_lib::lang_syntax::MommyLangSyntax::TextJoin => text::join(&tokens, symbols),
+        mommy_lib::lang_syntax::MommyLangSyntax::TextLength => text::measure(&tokens, symbols),
+        mommy_lib::lang_syntax::MommyLangSyntax::TextSlice => text::slice(&tokens, symbols),
+
         // --- Math (ALU) ---
         mommy_lib::lang_syntax::MommyLangSyntax::Math => {
             if tokens.len() < constants::ARGS_MIN_MATH {
@@ -57,9 +173,9 @@ fn parse_line(
             };
 
             alu::calculate_two(
-                &tokens[constants::IDX_MATH_TARGET],
+                tokens[constants::IDX_MATH_TARGET].as_str(),
                 operand,
-                &tokens[constants::IDX_MATH_SOURCE],
+                &tokens[constants::IDX_MATH_SOURCE..],
                 symbols,
             )
         }
@@ -70,45 +186,204 @@ fn parse_line(
 
         // --- Loops ---
         mommy_lib::lang_syntax::MommyLangSyntax::LoopStartBasic => {
+            let (tokens, label) = loops::split_label(&tokens, &blocks.loops)?;
+            let level = scope_stack.iter().filter(|s| **s == ScopeType::Loop).count();
+            let (c_code, counter) = loops::for_loop(&tokens, symbols, level)?;
             scope_stack.push(ScopeType::Loop);
-            Ok(loops::for_loop(&tokens))
+            blocks.open_loop(label, false, symbols);
+            declaration::insert_symbol(constants::TYPE_INT, &counter, symbols); // The counter dies with its loop
+            Ok(c_code)
         }
+        mommy_lib::lang_syntax::MommyLangSyntax::LoopEnd if scope_stack.last() == Some(&ScopeType::Loop) &&
+            blocks.loops.last().is_some_and(|frame| frame.runs_first()) => Err(responses::MommyLangError::MissingAgain),
         mommy_lib::lang_syntax::MommyLangSyntax::LoopEnd => match scope_stack.pop() {
-            Some(_) => Ok(loops::done()),
+            Some(ScopeType::Function) => {
+                let frame = chores.current.take().ok_or(responses::MommyLangError::UnexpectedDone)?;
+                chores.definitions.push(functions::end_function(frame, symbols));
+                Ok(String::new())
+            }
+            Some(ScopeType::Loop) => {
+                let released = text::release(&symbols.texts_in_scope()); // Every round, not just the last one
+                symbols.pop_scope();
+                let frame = blocks.loops.pop().ok_or(responses::MommyLangError::UnexpectedDone)?;
+                Ok(format!("{}{}", released, loops::end_loop(frame)))
+            }
+            Some(ScopeType::Pick | ScopeType::Arm | ScopeType::Otherwise) => {
+                let released = text::release(&symbols.texts_in_scope());
+                symbols.pop_scope();
+                let frame = blocks.picks.pop().ok_or(responses::MommyLangError::UnexpectedDone)?;
+                Ok(format!("{}{}", released, pick::end_pick(frame)))
+            }
+            Some(ScopeType::Condition | ScopeType::Alternative) => {
+                let released = text::release(&symbols.texts_in_scope());
+                symbols.pop_scope();
+                Ok(format!("{}{}", released, loops::done()))
+            }
+            Some(ScopeType::Placeholder) => { // Its first line already failed, there is no C to close
+                symbols.pop_scope();
+                Ok(String::new())
+            }
             None => Err(responses::MommyLangError::UnexpectedDone),
         },
         mommy_lib::lang_syntax::MommyLangSyntax::LoopBreak => {
             if !scope_stack.contains(&ScopeType::Loop) {
                 return Err(responses::MommyLangError::UnexpectedSatisfied);
             }
-            Ok(loops::satisfied())
+            blocks.leave_loop(&tokens, scope_stack, symbols, true)
+        }
+        mommy_lib::lang_syntax::MommyLangSyntax::LoopSkip => {
+            if !scope_stack.contains(&ScopeType::Loop) {
+                return Err(responses::MommyLangError::UnexpectedSkip);
+            }
+            blocks.leave_loop(&tokens, scope_stack, symbols, false)
         }
         mommy_lib::lang_syntax::MommyLangSyntax::LoopStartCondition => {
+            let (tokens, label) = loops::split_label(&tokens, &blocks.loops)?;
             if tokens.len() < 2 {
                 return Err(responses::MommyLangError::MissingArguments);
             }
+            let c_code = loops::while_loop(&tokens, symbols)?;
+            scope_stack.push(ScopeType::Loop);
+            blocks.open_loop(label, false, symbols);
+            Ok(c_code)
+        }
+        mommy_lib::lang_syntax::MommyLangSyntax::LoopStartFirst => {
+            let (tokens, label) = loops::split_label(&tokens, &blocks.loops)?;
+            if tokens.len() > 1 {
+                return Err(responses::MommyLangError::SyntaxError);
+            }
             scope_stack.push(ScopeType::Loop);
-            Ok(loops::while_loop(&tokens))
+            blocks.open_loop(label, true, symbols);
+            Ok(loops::do_while())
         }
+        mommy_lib::lang_syntax::MommyLangSyntax::LoopEndAgain => match (scope_stack.last(), blocks.loops.last()) {
+            (Some(ScopeType::Loop), Some(frame)) if frame.runs_first() => {
+                scope_stack.pop();
+                let released = text::release(&symbols.texts_in_scope());
+                symbols.pop_scope(); // The condition comes after the body's room is gone, in C too
+                let frame = blocks.loops.pop().ok_or(responses::MommyLangError::UnexpectedAgain)?;
+                Ok(format!("{}{}", released, loops::again_if(&tokens, frame, symbols)?))
+            }
+            _ => Err(responses::MommyLangError::UnexpectedAgain),
+        },
 
         // --- Conditions ---
         mommy_lib::lang_syntax::MommyLangSyntax::Condition => {
+            let c_code = conditions::ask(&tokens, symbols)?;
             scope_stack.push(ScopeType::Condition);
-            conditions::ask(&tokens)
+            symbols.push_scope();
+            Ok(c_code)
         }
         mommy_lib::lang_syntax::MommyLangSyntax::ConditionElse => match scope_stack.last() {
-            Some(ScopeType::Condition) => conditions::or(),
-            Some(ScopeType::Loop) | None => Err(responses::MommyLangError::OrphanElse),
-            _ => Err(responses::MommyLangError::SyntaxError),
+            Some(ScopeType::Condition) => {
+                let c_code = conditions::or(&tokens, symbols)?;
+                if !conditions::is_else_if(&tokens) {
+                    scope_stack.pop();
+                    scope_stack.push(ScopeType::Alternative); // the plain "or" closes the chain
+                }
+                let released = text::release(&symbols.texts_in_scope());
+                symbols.switch_branch(); // Every branch is its own room
+                Ok(format!("{}{}", released, c_code))
+            }
+            Some(ScopeType::Alternative) => Err(responses::MommyLangError::ElseAfterElse),
+            Some(ScopeType::Placeholder) => {
+                symbols.switch_branch();
+                Ok(String::new())
+            }
+            _ => Err(responses::MommyLangError::OrphanElse),
+        },
+
+        // --- Picks (Switch) ---
+        mommy_lib::lang_syntax::MommyLangSyntax::Pick => {
+            let (c_code, frame) = pick::start_pick(&tokens, symbols)?;
+            blocks.picks.push(frame);
+            scope_stack.push(ScopeType::Pick);
+            symbols.push_scope();
+            Ok(c_code)
+        }
+        mommy_lib::lang_syntax::MommyLangSyntax::PickArm => match (scope_stack.last(), blocks.picks.last_mut()) {
+            (Some(ScopeType::Pick | ScopeType::Arm), Some(frame)) => {
+                let c_code = pick::when(&tokens, frame, symbols)?;
+                scope_stack.pop();
+                scope_stack.push(ScopeType::Arm);
+                let released = text::release(&symbols.texts_in_scope());
+                symbols.switch_branch(); // Every arm is its own room
+                Ok(format!("{}{}", released, c_code))
+            }
+            (Some(ScopeType::Otherwise), _) => Err(responses::MommyLangError::ArmAfterOtherwise),
+            (Some(ScopeType::Placeholder), _) => {
+                symbols.switch_branch();
+                Ok(String::new())
+            }
+            _ => Err(responses::MommyLangError::ArmOutsidePick),
+        },
+        mommy_lib::lang_syntax::MommyLangSyntax::PickOtherwise => match (scope_stack.last(), blocks.picks.last_mut()) {
+            (Some(ScopeType::Pick | ScopeType::Arm), Some(frame)) => {
+                if tokens.len() > 1 {
+                    return Err(responses::MommyLangError::SyntaxError);
+                }
+                scope_stack.pop();
+                scope_stack.push(ScopeType::Otherwise); // "otherwise" closes the pick, like the plain "or"
+                let released = text::release(&symbols.texts_in_scope());
+                symbols.switch_branch();
+                Ok(format!("{}{}", released, pick::otherwise(frame)))
+            }
+            (Some(ScopeType::Otherwise), _) => Err(responses::MommyLangError::ArmAfterOtherwise),
+            (Some(ScopeType::Placeholder), _) => {
+                symbols.switch_branch();
+                Ok(String::new())
+            }
+            _ => Err(responses::MommyLangError::ArmOutsidePick),
+        },
+
+        // --- Chores (Functions) ---
+        mommy_lib::lang_syntax::MommyLangSyntax::FunctionStart => {
+            if !scope_stack.is_empty() {
+                return Err(responses::MommyLangError::NestedFunction);
+            }
+            let frame = functions::start_function(&tokens, symbols)?;
+            chores.prototypes.push(functions::prototype(&frame));
+            chores.current = Some(frame);
+            scope_stack.push(ScopeType::Function);
+            Ok(String::new())
+        }
+        mommy_lib::lang_syntax::MommyLangSyntax::FunctionReturn => match &chores.current {
+            Some(frame) => functions::give_back(&tokens, symbols, frame),
+            None => Err(responses::MommyLangError::UnexpectedGiveBack),
         },
+        mommy_lib::lang_syntax::MommyLangSyntax::FunctionCall => functions::call(&tokens, symbols),
+
+        // --- Records (Structs) and Choices (Enums) ---
+        mommy_lib::lang_syntax::MommyLangSyntax::Record => {
+            if !scope_stack.is_empty() {
+                return Err(responses::MommyLangError::NestedType);
+            }
+            globals.push(record::define(&tokens, symbols)?);
+            Ok(String::new())
+        }
+        mommy_lib::lang_syntax::MommyLangSyntax::Choices => {
+            if !scope_stack.is_empty() {
+                return Err(responses::MommyLangError::NestedType);
+            }
+            globals.push(choice::define(&tokens, symbols)?);
+            Ok(String::new())
+        }
 
         // --- System ---
-        mommy_lib::lang_syntax::MommyLangSyntax::ProgramEnd => Ok(constants::C_EXIT_SUCC.to_string()),
+        mommy_lib::lang_syntax::MommyLangSyntax::ProgramEnd => {
+            if chores.current.is_some() {
+                return Err(responses::MommyLangError::LeaveInsideFunction);
+            }
+            if symbols.track_heap() { // Tell on every block nobody gave back
+                return Ok(format!("{}report(); {}", constants::C_GUARD_PREFIX, constants::C_EXIT_SUCC));
+            }
+            Ok(constants::C_EXIT_SUCC.to_string())
+        }
         mommy_lib::lang_syntax::MommyLangSyntax::IncludeLib => {
             if tokens.len() < 2 {
                 return Err(responses::MommyLangError::MissingArguments);
             }
-            let include = package::add_package(&tokens[1])?;
+            let include = package::add_package(tokens[1].as_str())?;
             if !include.is_empty() && !includes.contains(&include) {
                 includes.push(include);
             }
@@ -120,18 +395,27 @@ fn parse_line(
     }
 }
 
-pub fn transpile_code_to_c(config: &Config) -> Result<(), String> {
+/// Returns the finished symbol table, so callers can inspect what the program declared.
+/// On failure, every mistake found, up to `config.max_errors`.
+pub fn transpile_code_to_c(config: &Config) -> Result<SymbolTable, Vec<Diagnostic>> {
     let mut scope_stack: Vec<ScopeType> = Vec::new();
+    let mut opened: Vec<Span> = Vec::new(); // Where each block on the scope stack started
 
     let content = fs::read_to_string(&config.input_path)
-        .map_err(|_| format!("{} :{}", responses::MommyLangError::CannotReadFile, config.input_path))?;
+        .map_err(|_| vec![Diagnostic::error(&responses::MommyLangError::CannotReadFile)])?;
 
     let mut output_file = fs::File::create(&config.c_path)
-        .map_err(|_| responses::MommyLangError::CannotCreateCFile.to_string())?;
+        .map_err(|_| vec![Diagnostic::error(&responses::MommyLangError::CannotCreateCFile)])?;
 
-    let mut symbol_table: HashMap<String, String> = HashMap::new();
+    let mut symbol_table = SymbolTable::new();
+    symbol_table.set_bounds_check(config.bounds_check);
+    symbol_table.set_track_heap(config.track_heap);
     let mut includes: Vec<String> = Vec::new(); // To store packages outside of main
+    let mut globals: Vec<String> = Vec::new(); // Structs, enums and top level constants, above the chores that use them
     let mut body_lines: Vec<String> = Vec::new();
+    let mut chores = Chores::default();
+    let mut blocks = Blocks::default();
+    let mut complaints = Complaints::new(config.max_errors);
 
     for (i, line) in content.lines().enumerate() {
         let trimmed_line = line.trim();
@@ -139,23 +423,75 @@ pub fn transpile_code_to_c(config: &Config) -> Result<(), String> {
             continue;
         }
 
-        let tokens = syntax_lexer::insert_token(trimmed_line);
-        let result = parse_line(tokens, &mut symbol_table, &mut scope_stack, &mut includes);
+        // Known even when the line does not lex, so a broken "ask" still opens its block
+        let first_word = trimmed_line.split_whitespace().next().unwrap_or_default();
+        let command = MommyLangSyntax::from_word(first_word);
+
+        symbol_table.set_line(i + 1);
+        let lexed = match syntax_lexer::tokenize(line, i + 1) {
+            Ok(lexed) => lexed,
+            Err(diagnostic) => {
+                open_anyway(&command, &[], &mut scope_stack, &mut symbol_table, &mut chores, &mut blocks);
+                let column = line.chars().take_while(|c| c.is_whitespace()).count() + 1;
+                opened.resize(scope_stack.len(), Span { line: i + 1, column, length: first_word.chars().count() });
+                if !complaints.report(diagnostic.with_source(&content)) {
+                    break;
+                }
+                continue;
+            }
+        };
+        let start = lexed.tokens.first().map_or(Span::default(), |token| token.span);
 
-        match result {
+        match parse_line(lexed.tokens.clone(), &mut symbol_table, &mut scope_stack, &mut includes, &mut globals, &mut chores, &mut blocks) {
             Ok(c_code) => {
-                if !c_code.is_empty() {
-                    body_lines.push(c_code);
+                opened.resize(scope_stack.len(), start); // An "or" or a "when" keeps the line of the block it continues
+
+                let c_code = match lexed.comment {
+                    Some(note) if config.keep_comments && !note.is_empty() => c_comment(c_code, &note),
+                    _ => c_code,
+                };
+                if c_code.is_empty() {
+                    continue;
+                }
+                match chores.current.as_mut() {
+                    Some(frame) => frame.body.push(c_code),
+                    None => body_lines.push(c_code),
                 }
             }
             Err(e) => {
-                return Err(format!("{}, {}: {}", constants::MSG_ERR_LINE, i + 1, e));
+                let span = diagnostic::blame(&e, &lexed.tokens, &symbol_table);
+                declare_anyway(&command, &lexed.tokens, &mut symbol_table);
+                open_anyway(&command, &lexed.tokens, &mut scope_stack, &mut symbol_table, &mut chores, &mut blocks);
+                opened.resize(scope_stack.len(), start);
+                if !complaints.report(Diagnostic::error(&e).at(span).with_source(&content)) {
+                    break;
+                }
             }
         }
     }
 
-    if !scope_stack.is_empty() {
-        return Err(responses::MommyLangError::UnclosedBlock.to_string());
+    for start in &opened { // Every block still open, pointing at the line that opened it
+        let unclosed = Diagnostic::error(&responses::MommyLangError::UnclosedBlock).at(Some(*start)).with_source(&content);
+        if !complaints.report(unclosed) {
+            break;
+        }
+    }
+    if !complaints.diagnostics.is_empty() {
+        return Err(complaints.diagnostics);
+    }
+    symbol_table.check_leaks();
+
+    // Text and runtime checks need their helpers, whether or not the user asked for "makeme words"/"makeme guard"
+    let runtimes = [
+        (constants::C_TEXT_PREFIX, package_list::MommyCustomPackages::Words),
+        (constants::C_GUARD_PREFIX, package_list::MommyCustomPackages::Guard),
+    ];
+    for (prefix, package) in runtimes {
+        let used = body_lines.iter().chain(&chores.definitions).any(|line| line.contains(prefix));
+        let package = package.to_string();
+        if used && !includes.contains(&package) {
+            includes.push(package);
+        }
     }
 
     for include in &includes {
@@ -165,6 +501,24 @@ pub fn transpile_code_to_c(config: &Config) -> Result<(), String> {
         writeln!(output_file).unwrap();
     }
 
+    for global in &globals {
+        writeln!(output_file, "{}", global).unwrap();
+    }
+    if !globals.is_empty() {
+        writeln!(output_file).unwrap();
+    }
+
+    for prototype in &chores.prototypes {
+        writeln!(output_file, "{}", prototype).unwrap();
+    }
+    if !chores.prototypes.is_empty() {
+        writeln!(output_file).unwrap();
+    }
+
+    for definition in &chores.definitions {
+        writeln!(output_file, "{}\n", definition).unwrap();
+    }
+
     writeln!(output_file, "{}", constants::C_MAIN_START).unwrap();
 
     for line in &body_lines {
@@ -173,14 +527,426 @@ pub fn transpile_code_to_c(config: &Config) -> Result<(), String> {
 
     writeln!(output_file, "{}", constants::C_MAIN_END).unwrap();
 
-    Ok(())
+    Ok(symbol_table)
+}
+
+/// Mommy's notes on a program that still compiled, each pointing at the variable it is about.
+pub fn warning_diagnostics(config: &Config, symbols: &SymbolTable) -> Vec<Diagnostic> {
+    let content = fs::read_to_string(&config.input_path).unwrap_or_default();
+
+    symbols.warnings().iter().map(|(line, warning)| {
+        let source = content.lines().nth(line.saturating_sub(1)).unwrap_or_default();
+        let span = syntax_lexer::tokenize(source, *line).ok()
+            .and_then(|lexed| diagnostic::name_span(&lexed.tokens, warning.name()));
+        Diagnostic::warning(warning).at(span).with_source(&content)
+    }).collect()
 }
 
 pub fn show_c_conversion_error(log: &Config) {
     let contents = fs::read_to_string(&log.c_path)
-        .expect(&responses::MommyLangError::CannotReadFile.to_string());
+        .unwrap_or_else(|_| panic!("{}", responses::MommyLangError::CannotReadFile));
     print_line(responses::MommyLangStatus::ConversionErrorStart);
     println!("{}", contents);
     print_line(responses::MommyLangStatus::ConversionErrorEnds);
     let _ = fs::remove_file(&log.c_path);
 }
+
+/// A declaration that failed still leaves its name behind, so the lines using it do not fail too.
+/// Whatever can still be read of it is kept: the type and size, a record's fields, a choice's members,
+/// a chore's parameter and return types.
+fn declare_anyway(command: &MommyLangSyntax, tokens: &[Token], symbols: &mut SymbolTable) {
+    let fresh = |index: usize, symbols: &SymbolTable| tokens.get(index)
+        .map(Token::as_str)
+        .filter(|name| symbols.get(name).is_none() && validate_syntax::ensure_valid_name(name).is_ok());
+    let position = |word: &str| tokens.iter().position(|token| token.is_keyword(word));
+
+    match command {
+        MommyLangSyntax::Declaration | MommyLangSyntax::Constant => {
+            let Some(in_index) = position(constants::KW_IN) else { return }; // The value can be more than one token
+            if let (Some(name), Some(raw_type)) = (fresh(in_index + 1, symbols), tokens.get(in_index + 3)) {
+                declaration::insert_symbol(raw_type.as_str(), name, symbols);
+            }
+        }
+        MommyLangSyntax::Array | MommyLangSyntax::String | MommyLangSyntax::Malloc => {
+            let (Some(name), Some(size), Some(raw_type)) = (fresh(constants::IDX_DECL_NAME, symbols),
+                tokens.get(constants::IDX_DECL_VALUE), tokens.get(constants::IDX_DECL_TYPE)) else { return };
+            let symbol = match command {
+                MommyLangSyntax::Malloc => Symbol::heap(raw_type.as_str(), size.as_str()),
+                _ => Symbol::array(raw_type.as_str(), size.as_str()),
+            };
+            symbols.insert(name, symbol);
+            record::adopt(name, raw_type.as_str(), symbols);
+            choice::adopt(name, raw_type.as_str(), symbols);
+        }
+        MommyLangSyntax::Record => {
+            let Some(name) = fresh(constants::IDX_RECORD_NAME, symbols) else { return };
+            let fields = tokens.get(constants::IDX_RECORD_KEY_HAS + 1..)
+                .and_then(|fields| functions::parse_params(fields).ok())
+                .unwrap_or_default();
+            symbols.insert(name, Symbol::record(name, fields));
+        }
+        MommyLangSyntax::Choices => {
+            let Some(name) = fresh(constants::IDX_RECORD_NAME, symbols) else { return };
+            let listed = syntax_lexer::join(tokens.get(constants::IDX_RECORD_KEY_HAS + 1..).unwrap_or_default());
+            let mut members: Vec<String> = Vec::new();
+            for member in listed.split(constants::SYM_COMMA).map(str::trim) {
+                if symbols.get(member).is_none() && validate_syntax::ensure_valid_name(member).is_ok() && !members.iter().any(|m| m == member) {
+                    members.push(member.to_string());
+                }
+            }
+            choice::register(name, &members, symbols);
+        }
+        MommyLangSyntax::FunctionStart => {
+            let Some(name) = fresh(constants::IDX_FUNC_NAME, symbols) else { return };
+            let gives_index = position(constants::KW_GIVES);
+            let return_type = gives_index.and_then(|index| tokens.get(index + 1)).map_or(constants::C_KW_VOID, Token::as_str);
+            // Every type written after an "as", even one mommy does not know, so calls are still counted
+            let param_types = tokens.iter().enumerate()
+                .take(gives_index.unwrap_or(tokens.len()))
+                .skip(constants::IDX_FUNC_KEY_NEEDS + 1)
+                .filter(|(index, _)| tokens[index - 1].is_keyword(constants::KW_AS))
+                .map(|(_, token)| token.as_str().trim_end_matches(constants::SYM_COMMA).to_string())
+                .collect();
+            symbols.insert(name, Symbol::function(return_type, param_types));
+        }
+        _ => {}
+    }
+}
+
+/// A line that should have opened a block, or started its next branch, failed. The block is opened anyway,
+/// so the body is still checked and its "done" still closes something. A loop keeps its counter and name,
+/// a chore its parameters. A block that cannot be trusted at all becomes a placeholder, whose "or", "when"
+/// and "done" are only bookkeeping.
+fn open_anyway(
+    command: &MommyLangSyntax,
+    tokens: &[Token],
+    scope_stack: &mut Vec<ScopeType>,
+    symbols: &mut SymbolTable,
+    chores: &mut Chores,
+    blocks: &mut Blocks,
+) {
+    let open_placeholder = |scope_stack: &mut Vec<ScopeType>, symbols: &mut SymbolTable| {
+        scope_stack.push(ScopeType::Placeholder);
+        symbols.push_scope();
+    };
+
+    match command {
+        MommyLangSyntax::Condition => {
+            scope_stack.push(ScopeType::Condition);
+            symbols.push_scope();
+        }
+        MommyLangSyntax::LoopStartBasic | MommyLangSyntax::LoopStartCondition | MommyLangSyntax::LoopStartFirst => {
+            let (header, label) = loops::split_label(tokens, &blocks.loops).unwrap_or_else(|_| (tokens.to_vec(), None));
+            let level = scope_stack.iter().filter(|s| **s == ScopeType::Loop).count();
+            let counter = match command {
+                MommyLangSyntax::LoopStartBasic => loops::counter_name(&header, symbols, level),
+                _ => None,
+            };
+            scope_stack.push(ScopeType::Loop);
+            blocks.open_loop(label, matches!(command, MommyLangSyntax::LoopStartFirst), symbols);
+            if let Some(counter) = counter && symbols.get(&counter).is_none() && validate_syntax::ensure_valid_name(&counter).is_ok() {
+                declaration::insert_symbol(constants::TYPE_INT, &counter, symbols);
+            }
+        }
+        MommyLangSyntax::FunctionStart if chores.current.is_none() => {
+            chores.current = Some(functions::start_anyway(tokens, symbols));
+            scope_stack.push(ScopeType::Function);
+        }
+        MommyLangSyntax::ConditionElse => match scope_stack.last() {
+            Some(ScopeType::Condition) => {
+                if !conditions::is_else_if(tokens) {
+                    scope_stack.pop();
+                    scope_stack.push(ScopeType::Alternative);
+                }
+                symbols.switch_branch();
+            }
+            Some(ScopeType::Alternative | ScopeType::Placeholder) => symbols.switch_branch(),
+            _ => open_placeholder(scope_stack, symbols), // An "or" with no "ask" still has a "done"
+        },
+        MommyLangSyntax::PickArm | MommyLangSyntax::PickOtherwise => match scope_stack.last() {
+            Some(ScopeType::Pick | ScopeType::Arm) => {
+                scope_stack.pop();
+                scope_stack.push(match command {
+                    MommyLangSyntax::PickArm => ScopeType::Arm,
+                    _ => ScopeType::Otherwise,
+                });
+                symbols.switch_branch();
+            }
+            Some(ScopeType::Otherwise | ScopeType::Placeholder) => symbols.switch_branch(),
+            _ => open_placeholder(scope_stack, symbols),
+        },
+        _ if command.opens_block() => open_placeholder(scope_stack, symbols), // "pick", or a chore inside a chore
+        _ => {}
+    }
+}
+
+/// "--keep-comments": the comment rides along on its line, or gets a line of its own.
+fn c_comment(c_code: String, note: &str) -> String {
+    let note = note.trim_end_matches(constants::SYM_BACKSLASH); // A "\" at the end would swallow the next C line
+    if c_code.is_empty() {
+        return format!("{}{}", constants::C_COMMENT_PREFIX, note);
+    }
+    format!("{} {}{}", c_code, constants::C_COMMENT_PREFIX, note)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes `source` to a scratch file and reads the command line flags like mommy_lang does.
+    fn scratch(file: &str, source: &str, flags: &[&str]) -> Config {
+        let input_path = std::env::temp_dir().join(file).to_string_lossy().to_string();
+        fs::write(&input_path, source).unwrap();
+        let mut args = vec![String::new(), input_path];
+        args.extend(flags.iter().map(|flag| flag.to_string()));
+        Config::new(&args).unwrap()
+    }
+
+    fn clean_up(config: &Config) {
+        for path in [&config.input_path, &config.c_path, &config.exe_path] {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    fn messages(diagnostics: &[Diagnostic]) -> String {
+        diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Transpiles `source` from a scratch file and hands back the C, or what mommy said about it.
+    fn c_of(file: &str, source: &str, flags: &[&str]) -> Result<String, String> {
+        let config = scratch(file, source, flags);
+        let result = transpile_code_to_c(&config)
+            .map(|_| fs::read_to_string(&config.c_path).unwrap())
+            .map_err(|diagnostics| messages(&diagnostics));
+        clean_up(&config);
+        result
+    }
+
+    /// Transpiles `source` from a scratch file and hands back the errors.
+    fn errors_in(file: &str, source: &str) -> Vec<Diagnostic> {
+        errors_up_to(file, source, constants::DEFAULT_MAX_ERRORS)
+    }
+
+    fn errors_up_to(file: &str, source: &str, max_errors: usize) -> Vec<Diagnostic> {
+        transpile(file, source, max_errors).err().unwrap_or_default()
+    }
+
+    fn transpile(file: &str, source: &str, max_errors: usize) -> Result<SymbolTable, Vec<Diagnostic>> {
+        let max_errors = max_errors.to_string();
+        let config = scratch(file, source, &[constants::FLAG_MAX_ERRORS, &max_errors]);
+        let result = transpile_code_to_c(&config);
+        clean_up(&config);
+        result
+    }
+
+    /// Transpiles, builds with gcc and runs the program, the way mommy_lang does it.
+    fn run_program(file: &str, source: &str, flags: &[&str]) -> std::process::Output {
+        run_with_input(file, source, flags, "")
+    }
+
+    /// Same as run_program, with `input` typed into the program.
+    fn run_with_input(file: &str, source: &str, flags: &[&str], input: &str) -> std::process::Output {
+        let config = scratch(file, source, flags);
+        if let Err(error) = transpile_code_to_c(&config) {
+            panic!("{} did not transpile: {}", file, messages(&error));
+        }
+        if let Err(error) = crate::pipeline::compile_to_gcc(&config) {
+            panic!("{} did not build: {}", file, error);
+        }
+        let mut program = std::process::Command::new(&config.exe_path)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        program.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+        let output = program.wait_with_output().unwrap();
+        clean_up(&config);
+        output
+    }
+
+    #[test]
+    fn plain_or_closes_the_chain() {
+        let chain = "mayihave 1 in x as int\nask if x > 1\nsay x\nor ask if x > 0\nsay x\nor\nsay x\ndone\n";
+        let c = c_of("else_if_chain.mommy", chain, &[]).unwrap();
+        assert!(c.contains("}else if (x > 0) {") && c.contains("}else {"), "{}", c);
+
+        let late = "mayihave 1 in x as int\nask if x > 1\nor\nor ask if x > 0\ndone\n";
+        let error = c_of("else_if_after_or.mommy", late, &[]).unwrap_err();
+        assert!(error.ends_with(&responses::MommyLangError::ElseAfterElse.to_string()), "{}", error);
+    }
+
+    #[test]
+    fn bad_variable_index_stops_with_code_3() {
+        let source = "group 3 in xs as int\nmayihave 5 in i as int\nreplace xs in i with 1\nsay xs in 0\nleave\n";
+        let output = run_program("bounds_checked.mommy", source, &[constants::FLAG_BOUNDS_CHECK]);
+        assert_eq!(output.status.code(), Some(3));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Line 3,"));
+
+        let unchecked = c_of("bounds_unchecked.mommy", source, &[constants::FLAG_NO_BOUNDS_CHECK]).unwrap();
+        assert!(unchecked.contains("xs[i] = 1;"), "{}", unchecked);
+    }
+
+    #[test]
+    fn zero_divisor_stops_with_code_4() {
+        let source = "mayihave 10 in x as int\nmayihave 0 in y as int\ndivide x with y\nsay x\nleave\n";
+        let output = run_program("zero_divisor.mommy", source, &[]);
+        assert_eq!(output.status.code(), Some(4));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Line 3,"));
+    }
+
+    #[test]
+    fn float_divisors_keep_their_fraction() {
+        let source = "mayihave 1.0 in x as float\nmayihave 0.5 in y as float\ndivide x with y\nsay x\n\
+            replace y with 0.0\ndivide x with y\nleave\n";
+        let output = run_program("float_divisor.mommy", source, &[]);
+        assert_eq!(output.status.code(), Some(4));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.starts_with("2"), "{}", stdout); // Not cut to 0, which would have stopped it right away
+        assert!(stdout.contains("Line 6,"), "{}", stdout);
+    }
+
+    #[test]
+    fn heap_size_below_one_stops_with_code_5() {
+        let source = "mayihave 0 in n as int\nibegyou n in xs as int\nleave\n";
+        let output = run_program("heap_size.mommy", source, &[]);
+        assert_eq!(output.status.code(), Some(5));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Line 2,"));
+    }
+
+    #[test]
+    fn tracked_heap_reports_leftovers_at_leave() {
+        let source = "ibegyou 2 in kept as int\nibegyou 2 in returned as int\ntakeitback returned\nleave\n";
+        let output = run_program("track_heap.mommy", source, &[constants::FLAG_TRACK_HEAP]);
+        assert_eq!(output.status.code(), Some(0));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("'kept' from line 1 was never given back"), "{}", stdout);
+        assert!(!stdout.contains("'returned'"), "{}", stdout);
+    }
+
+    #[test]
+    fn tracked_heap_keeps_its_line_when_it_grows() {
+        let source = "ibegyou 2 in kept as int\nsay \"growing\"\nibegyoumore 4 in kept\nleave\n";
+        let output = run_program("track_resize.mommy", source, &[constants::FLAG_TRACK_HEAP]);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("'kept' from line 1 was never given back"), "{}", stdout);
+    }
+
+    #[test]
+    fn satisfied_in_a_pick_leaves_the_loop() {
+        let source = "punishme 5 as i\npick i\nwhen 2\nsatisfied\notherwise\nsay i\ndone\ndone\nsay \"out\"\nleave\n";
+        let output = run_program("pick_satisfied.mommy", source, &[]);
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "0\n1\nout\n");
+
+        let after_otherwise = "mayihave 1 in x as int\npick x\notherwise\nwhen 1\ndone\nleave\n";
+        assert!(c_of("pick_late_arm.mommy", after_otherwise, &[]).is_err());
+        let before_arm = "mayihave 1 in x as int\npick x\nsay x\nwhen 1\ndone\nleave\n";
+        let error = c_of("pick_outside_arm.mommy", before_arm, &[]).unwrap_err();
+        assert!(error.ends_with(&responses::MommyLangError::OutsideArm.to_string()), "{}", error);
+    }
+
+    #[test]
+    fn named_loops_skip_and_leave_from_inside() {
+        let source = "punishme i from 0 to 2 named outer\npunishme j from 0 to 2\nask if j == 1\nskip outer\ndone\n\
+            ask if i == 2\nsatisfied outer\ndone\nsay i\ndone\ndone\nsay \"out\"\nleave\n";
+        let output = run_program("named_loops.mommy", source, &[]);
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "0\n1\nout\n");
+
+        let unknown = "punishme 3 as i\nsatisfied nowhere\ndone\nleave\n";
+        assert!(c_of("named_unknown.mommy", unknown, &[]).is_err());
+    }
+
+    #[test]
+    fn punishmefirst_runs_once_and_skip_checks_againif() {
+        let source = "mayihave 5 in n as int\npunishmefirst\nsay n\nagainif n < 3\n\
+            mayihave 0 in k as int\npunishmefirst\nadd k with 1\nask if k == 2\nskip\ndone\nsay k\nagainif k < 4\nleave\n";
+        let output = run_program("do_while.mommy", source, &[]);
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "5\n1\n3\n4\n");
+
+        assert!(c_of("againif_alone.mommy", "mayihave 1 in n as int\nagainif n < 3\nleave\n", &[]).is_err());
+        assert!(c_of("punishmefirst_done.mommy", "punishmefirst\ndone\nleave\n", &[]).is_err());
+    }
+
+    #[test]
+    fn keep_comments_copies_them_into_c() {
+        let source = "# the setup\nmayihave 3 in total as int psst three of them\nsay \"#1\"\nleave\n";
+        let kept = c_of("keep_comments.mommy", source, &[constants::FLAG_KEEP_COMMENTS]).unwrap();
+        assert!(kept.contains("// the setup"), "{}", kept);
+        assert!(kept.contains("int total = 3; // three of them"), "{}", kept);
+        assert!(kept.contains("printf(\"#1\\n\");"), "{}", kept);
+
+        let dropped = c_of("drop_comments.mommy", source, &[]).unwrap();
+        assert!(!dropped.contains("//"), "{}", dropped);
+    }
+
+    #[test]
+    fn failed_declaration_is_reported_once() {
+        let cases = [
+            ("mayihave (1 + 2 in x as int\nsay x\n", constants::KW_VAR_DEC),
+            ("group 3 in xs as int with 1, 2.5\nreplace xs in 0 with 4\nsay xs in 0\n", constants::KW_ARRAY_DEC),
+            ("record student has name as String, name as float\nmayihave null in ann as student\nsay ann.name\n", constants::KW_RECORD),
+            ("choices mood has tired, tired, happy\nmayihave happy in today as mood\nsay today\n", constants::KW_CHOICES),
+            ("chore twice needs n as int gives number\ngiveback n * 2\ndone\nmayihave twice(3) in x as int\nsay x\n", constants::KW_FUNC_DEC),
+        ];
+
+        for (index, (source, statement)) in cases.iter().enumerate() {
+            let errors = errors_in(&format!("declare_anyway_{}{}", index, constants::EXT_SOURCE), &format!("{}leave\n", source));
+            let messages: Vec<&str> = errors.iter().map(|error| error.message.as_str()).collect();
+            assert_eq!(errors.len(), 1, "a failed {} left more than one error: {:?}", statement, messages);
+            assert_eq!(errors[0].span.map(|span| span.line), Some(1), "{}", statement);
+        }
+    }
+
+    fn lines_of(errors: &[Diagnostic]) -> Vec<Option<usize>> {
+        errors.iter().map(|error| error.span.map(|span| span.line)).collect()
+    }
+
+    #[test]
+    fn failed_block_header_still_checks_the_body() {
+        let cases = [
+            ("mayihave 1 in x as int\nask if x == \"a\"\nsay ghost\nor\nsay phantom\ndone\n", vec![Some(2), Some(3), Some(5)]),
+            ("punishme ghost as row named outer\nsay row\nsatisfied outer\nsay phantom\ndone\n", vec![Some(1), Some(4)]),
+            ("pick \"a\"\nwhen 1\nsay ghost\nwhen 2\nsay phantom\ndone\n", vec![Some(1), Some(3), Some(5)]),
+            ("chore twice needs n as int gives number\nsay n\nsay ghost\ngiveback phantom\ndone\n", vec![Some(1), Some(3), Some(4)]),
+        ];
+
+        for (index, (source, lines)) in cases.iter().enumerate() {
+            let errors = errors_in(&format!("open_anyway_{}{}", index, constants::EXT_SOURCE), &format!("{}leave\n", source));
+            let messages: Vec<&str> = errors.iter().map(|error| error.message.as_str()).collect();
+            assert_eq!(&lines_of(&errors), lines, "{:?} in\n{}", messages, source);
+        }
+    }
+
+    #[test]
+    fn too_many_errors_only_when_one_more_comes_in() {
+        let exactly_two = "say ghost\nsay phantom\nmayihave 1 in x as int\nsay x\nleave\n";
+        let errors = errors_up_to("cap_exact.mommy", exactly_two, 2);
+        assert_eq!(lines_of(&errors), [Some(1), Some(2)]);
+
+        let errors = errors_up_to("cap_over.mommy", "say ghost\nsay phantom\nsay spirit\nsay shade\nleave\n", 2);
+        assert_eq!(lines_of(&errors), [Some(1), Some(2), None]);
+        assert_eq!(errors[2].code, responses::MommyLangError::TooManyErrors(2).code());
+
+        // The last line fills the cap, the block it left open is one too many
+        let errors = errors_up_to("cap_unclosed.mommy", "say ghost\nask if yes\nsay phantom\n", 2);
+        assert_eq!(lines_of(&errors), [Some(1), Some(3), None]);
+        assert_eq!(errors[2].code, responses::MommyLangError::TooManyErrors(2).code());
+    }
+
+    #[test]
+    fn roman_sample_reads_xiv_as_14() {
+        let source = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../sandbox/roman.mommy")).unwrap();
+        let output = run_with_input("roman.mommy", &source, &[], "XIV\n");
+        assert_eq!(output.status.code(), Some(0));
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.ends_with("Decimal Value:\n14\n"), "{}", stdout);
+    }
+
+    #[test]
+    fn taken_counter_name_is_warned_about() {
+        let source = "mayihave 0 in i as int\npunishme 3\nsay i\ndone\nleave\n";
+        let Ok(symbols) = transpile("counter_taken.mommy", source, constants::DEFAULT_MAX_ERRORS) else { panic!("did not compile") };
+        let warnings: Vec<String> = symbols.warnings().iter().map(|(line, warning)| format!("{} {}", line, warning.code())).collect();
+        assert_eq!(warnings, ["2 W003"]);
+    }
+}
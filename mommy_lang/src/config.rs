@@ -5,6 +5,10 @@ pub struct Config {
     pub input_path: String,
     pub c_path: String,
     pub exe_path: String,
+    pub bounds_check: bool,
+    pub track_heap: bool,
+    pub keep_comments: bool,
+    pub max_errors: usize,
 }
 
 impl Config {
@@ -22,10 +26,33 @@ impl Config {
         let c_path = input_path.replace(constants::EXT_SOURCE, constants::EXT_C);
         let exe_path = input_path.replace(constants::EXT_SOURCE, constants::EXT_EXE);
 
+        // Debug builds check variable indices unless told otherwise, release builds only when asked
+        let flags = &args[constants::IDX_FILE_NAME + 1..];
+        let bounds_check = if flags.iter().any(|f| f == constants::FLAG_NO_BOUNDS_CHECK) {
+            false
+        } else {
+            cfg!(debug_assertions) || flags.iter().any(|f| f == constants::FLAG_BOUNDS_CHECK)
+        };
+
+        let track_heap = flags.iter().any(|f| f == constants::FLAG_TRACK_HEAP);
+        let keep_comments = flags.iter().any(|f| f == constants::FLAG_KEEP_COMMENTS);
+
+        let max_errors = match flags.iter().position(|f| f == constants::FLAG_MAX_ERRORS) {
+            Some(index) => flags.get(index + 1)
+                .and_then(|limit| limit.parse::<usize>().ok())
+                .filter(|limit| *limit > 0)
+                .ok_or_else(|| responses::MommyLangError::InvalidErrorLimit.to_string())?,
+            None => constants::DEFAULT_MAX_ERRORS,
+        };
+
         Ok(Config {
             input_path,
             c_path,
             exe_path,
+            bounds_check,
+            track_heap,
+            keep_comments,
+            max_errors,
         })
     }
 }
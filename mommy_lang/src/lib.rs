@@ -0,0 +1,29 @@
+//! # MommyLang Compiler Library
+//!
+//! The compiler without the command line around it. `main.rs` is one caller, any other tool
+//! (an editor, a linter, a test) can be another.
+//!
+//! `transpile_code_to_c` reads `config.input_path`, writes the C to `config.c_path` and hands back
+//! the finished symbol table. `SymbolTable::declarations()` then lists everything the program declared:
+//!
+//! ```no_run
+//! use mommy_lang::compiler::transpile_code_to_c;
+//! use mommy_lang::config::Config;
+//!
+//! let args = ["mommy_lang".to_string(), "sandbox/hello.mommy".to_string()];
+//! let config = Config::new(&args).unwrap();
+//!
+//! if let Ok(symbols) = transpile_code_to_c(&config) {
+//!     for (name, symbol) in symbols.declarations() {
+//!         println!("{} is a {:?}, declared on line {}", name, symbol.kind, symbol.line);
+//!     }
+//! }
+//! ```
+//!
+//! On failure it gives back every diagnostic instead, render them with `Diagnostic::render`.
+//!
+//!
+
+pub mod config;
+pub mod compiler;
+pub mod pipeline;
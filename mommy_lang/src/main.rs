@@ -11,18 +11,14 @@
 //!
 //!
 
-mod config;
-mod compiler;
-mod pipeline;
-
 use std::env;
 
 use mommy_lib::responses;
 use mommy_lib::shell_format::{print_line, eprint_line};
 
-use crate::compiler::{show_c_conversion_error, transpile_code_to_c};
-use crate::config::Config;
-use crate::pipeline::{compile_to_gcc, run_mommy_file};
+use mommy_lang::compiler::{show_c_conversion_error, transpile_code_to_c, warning_diagnostics};
+use mommy_lang::config::Config;
+use mommy_lang::pipeline::{compile_to_gcc, run_mommy_file};
 
 
 fn main() {
@@ -37,13 +33,28 @@ fn main() {
         }
     };
     
-    if let Err(e) = transpile_code_to_c(&config){ //Convert mommy_lang to C
-        print_line(responses::MommyLangError::ErrorBegins);
-        eprint_line(e);
-        show_c_conversion_error(&config); // show fragmented c code
-        eprint_line(responses::MommyLangError::ConvertLangFailed);
-        print_line(responses::MommyLangError::ErrorEnds);
-        std::process::exit(1);
+    let symbols = match transpile_code_to_c(&config){ //Convert mommy_lang to C
+        Ok(symbols) => symbols,
+        Err(diagnostics) => {
+            print_line(responses::MommyLangError::ErrorBegins);
+            for (index, diagnostic) in diagnostics.iter().enumerate() {
+                if index > 0 {
+                    eprint_line(""); // A gap between complaints
+                }
+                diagnostic.render(&config.input_path).into_iter().for_each(eprint_line);
+            }
+            show_c_conversion_error(&config); // show fragmented c code
+            eprint_line(responses::MommyLangError::ConvertLangFailed);
+            print_line(responses::MommyLangError::ErrorEnds);
+            std::process::exit(1);
+        }
+    };
+
+    if !symbols.warnings().is_empty() { // It compiles, but mommy has notes
+        print_line(responses::MommyLangStatus::ResultOkButConfused);
+        for warning in warning_diagnostics(&config, &symbols) {
+            warning.render(&config.input_path).into_iter().for_each(eprint_line);
+        }
     }
 
     if let Err(e) = compile_to_gcc(&config){ //use GCC to create exe file for the converted C
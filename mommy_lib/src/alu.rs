@@ -2,14 +2,20 @@
 //!
 //! This is the math crate of mommylang.
 //!
-//! Language Syntax: "target = target operator value/variable"
+//! Language Syntax: "target = target operator (expression)"
+//! Conversion Syntax: "turn value into target"
 //!
 //! Note:
-//! 1. It can only do operations on one variable at a time, such as "x = x + 10",
+//! 1. The target is still one variable at a time, such as "x = x + 10",
 //!    which is sort of similar to assembly language syntax.
-//! 2. Pemdas or AST is not supported. If you want to do multiple operations,
-//!    you need to chain them sequentially.
-//! 3. There is no support for variable type conversion.
+//! 2. The value can be a full expression now, "add total with (a + b) * 2".
+//!    Precedence and parentheses are handled by the expression crate.
+//! 3. Conversion goes between int, float and ascii in either direction, "turn total into average".
+//!    The target decides the type, and float to int cuts off the decimals like C does.
+//!    A text and an ascii array can be turned into each other too, the text crate handles that.
+//! 4. "divide" and "mod" by anything that works out to zero before the program runs (0, 0.0, 0e0, 2 - 2 ...)
+//!    are rejected here. Every other divisor that is not a fixed number, here or anywhere inside an expression
+//!    ("replace y with y / (x - 4)"), gets a runtime guard that names it and the .mommy line, and exits with code 4.
 //!
 //!
 //!
@@ -18,42 +24,131 @@
 //!
 //!
 //!
-use std::collections::HashMap;
 use crate::responses::{MommyLangError};
 use crate::constants;
+use crate::declaration;
+use crate::expression::{self, Expr};
+use crate::lang_enums::SymbolKind;
+use crate::symbol_table::SymbolTable;
+use crate::text;
+use crate::validate_syntax;
+use crate::syntax_lexer::Token;
 
-pub fn calculate_two(target: &str, operator: &str, value: &str, symbols: &HashMap<String, String>) -> Result<String, MommyLangError> {
+pub fn calculate_two(target: &str, operator: &'static str, value: &[Token], symbols: &SymbolTable) -> Result<String, MommyLangError> {
     // Let us validate it first, because it might be an illegal operation like walter's meth lab.
-    validate_operation(target, operator, value, symbols)?;
-    Ok(format!("{} = {} {} {};", target, target, operator, value))
+    let value = validate_operation(target, operator, value, symbols)?;
+
+    // "multiply x with a + 1" means x * (a + 1), not x * a + 1
+    let value = if value.is_compound() { Expr::Group(Box::new(value)) } else { value };
+    let math = Expr::Binary(Box::new(Expr::Variable(target.to_string())), operator, Box::new(value));
+    Ok(format!("{} = {};", target, expression::to_c(math, symbols)?)) // A divisor that is not fixed gets its guard here
 }
 
 
+pub fn convert(tokens: &[Token], symbols: &SymbolTable) -> Result<String, MommyLangError> {
+    // Syntax: turn <VALUE/EXPRESSION> into <TARGET>
+
+    if tokens.len() < constants::ARGS_MIN_CONVERT {
+        return Err(MommyLangError::MissingArguments);
+    }
+
+    let into_index = tokens.iter().position(|t| t.is_keyword(constants::KW_INTO))
+        .ok_or(MommyLangError::SyntaxError)?;
+    if into_index < 2 || into_index + 2 != tokens.len() {
+        return Err(MommyLangError::SyntaxError);
+    }
+
+    let target = tokens[into_index + 1].as_str();
+    let symbol = symbols.lookup(target)?;
+    validate_syntax::ensure_mutable(symbol)?;
+
+    if text::is_managed(symbol) || text::ascii_size(symbol).is_some() {
+        return text::convert(&tokens[1..into_index], target, symbols);
+    }
+
+    if symbol.kind != SymbolKind::Scalar || !matches!(symbol.data_type.as_str(),
+        constants::TYPE_INT | constants::TYPE_FLOAT | constants::TYPE_ASCII) {
+        return Err(MommyLangError::TypeMismatch);
+    }
+
+    let value = expression::parse_operand(&tokens[1..into_index], symbols)?; // Numbers only, no words or groups
+    let c_type = declaration::get_c_type(&symbol.data_type);
+
+    if value.is_compound() {
+        return Ok(format!("{} = ({})({});", target, c_type, value));
+    }
+    Ok(format!("{} = ({}){};", target, c_type, value))
+}
+
 fn validate_operation(
     target: &str,
     operator: &str,
-    value: &str,
-    symbols: &HashMap<String, String>
-) -> Result<(), MommyLangError> {
-
-    let var_type = match symbols.get(target) { // Does the target variable exist?
-        Some(t) => t,
-        None => return Err(MommyLangError::UndeclaredVariable),
-    };
-
-    if !symbols.contains_key(value){ // It might be a variable, check our hashmap.....
-        if value.parse::<f64>().is_err(){ // if it is not a variable, can it be a number?
-            return Err(MommyLangError::UndeclaredVariable);
-        }
-    }
+    value: &[Token],
+    symbols: &SymbolTable
+) -> Result<Expr, MommyLangError> {
+
+    let symbol = symbols.lookup(target)?; // Does the target variable exist?
+    validate_syntax::ensure_mutable(symbol)?;
 
-    if operator == constants::C_OP_DIV && value == constants::C_VAL_ZERO { // No division on 0!
+    let value = expression::read_operand(value, symbols)?; // Every variable inside is checked here
+
+    if (operator == constants::C_OP_DIV || operator == constants::C_OP_MOD) && value.is_literal_zero() { // No division on 0!
         return Err(MommyLangError::DivideByZero);
     }
 
-    if var_type == constants::TYPE_STRING  || var_type == constants::C_TYPE_CHAR_PTR { // No math on Strings!
+    if symbol.kind == SymbolKind::Scalar && symbol.is_text() { // No math on Strings!
     return Err(MommyLangError::MathOnString);
     }
 
-    Ok(()) // Valid operation!
+    Ok(value) // Valid operation!
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{declared, tokens};
+
+    fn symbols() -> SymbolTable {
+        declared(&[
+            "mayihave 0 in whole as int",
+            "mayihave 0.5 in part as float",
+            "mayihave 65 in letter as ascii",
+            "mayihave \"Ann\" in name as String",
+        ])
+    }
+
+    #[test]
+    fn target_decides_the_type() {
+        let symbols = symbols();
+        assert_eq!(convert(&tokens("turn 3.7 into whole"), &symbols).unwrap(), "whole = (int)3.7;");
+        assert_eq!(convert(&tokens("turn whole + 1 into part"), &symbols).unwrap(), "part = (float)(whole + 1);");
+        assert_eq!(convert(&tokens("turn part into letter"), &symbols).unwrap(), "letter = (int)part;");
+    }
+
+    #[test]
+    fn only_numbers_turn() {
+        let symbols = symbols();
+        assert_eq!(convert(&tokens("turn whole into name"), &symbols), Err(MommyLangError::TypeMismatch));
+        assert_eq!(convert(&tokens("turn name into whole"), &symbols), Err(MommyLangError::MathOnString));
+        assert_eq!(convert(&tokens("turn whole into ghost"), &symbols), Err(MommyLangError::UndeclaredVariable));
+        assert_eq!(convert(&tokens("turn whole to part"), &symbols), Err(MommyLangError::SyntaxError));
+        assert_eq!(convert(&tokens("turn into whole"), &symbols), Err(MommyLangError::MissingArguments));
+    }
+
+    #[test]
+    fn zero_divisors_are_caught() {
+        let symbols = symbols();
+        for zero in ["0", "0.0", "00", "-0", "(0)"] {
+            assert_eq!(calculate_two("whole", constants::C_OP_DIV, &tokens(zero), &symbols),
+                Err(MommyLangError::DivideByZero), "{}", zero);
+            assert_eq!(calculate_two("whole", constants::C_OP_MOD, &tokens(zero), &symbols),
+                Err(MommyLangError::DivideByZero), "{}", zero);
+        }
+        assert_eq!(calculate_two("whole", constants::C_OP_DIV, &tokens("2"), &symbols).unwrap(), "whole = whole / 2;");
+        assert_eq!(calculate_two("whole", constants::C_OP_DIV, &tokens("whole"), &symbols).unwrap(),
+            "whole = whole / _mommy_guard_divisor(whole, \"whole\", 0);");
+        assert_eq!(calculate_two("part", constants::C_OP_DIV, &tokens("part"), &symbols).unwrap(),
+            "part = part / _mommy_guard_divisor_float(part, \"part\", 0);");
+    }
 }
@@ -0,0 +1,99 @@
+//!
+//!
+//! This is the choice (enum) crate of mommylang.
+//!
+//! Language Syntax:
+//! - Definition: "choices mood has happy, tired, angry"
+//! - Declaration: "mayihave tired in today as mood", "always happy in best as mood"
+//! - Compare: "ask if today == angry", "punishmeif today != happy"
+//! - Groups and heap blocks: "group 7 in week as mood", "replace week in 0 with happy"
+//! - Print: "say today" prints the member's name, "tired"
+//!
+//! Note:
+//! 1. Choices become C enums ("typedef enum { happy, tired, angry } mood;"), written above the chores and main.
+//! 2. Choices are defined at the top level, so every chore can see them and their members.
+//! 3. A member only fits its own choice. "today == 2" or comparing a mood with a color is rejected.
+//! 4. No math on choices, they are names, not numbers.
+//!
+//! Symbol: kind Choice, data_type is the choice name, members are the names in order.
+//! A member, variable, group or heap block of a choice type carries the same members.
+//!
+//!
+use crate::constants;
+use crate::lang_enums::SymbolKind;
+use crate::responses::MommyLangError;
+use crate::symbol_table::{Symbol, SymbolTable};
+use crate::validate_syntax;
+use crate::syntax_lexer::{self, Token};
+
+// ================================================================
+// PUBLIC FUNCTIONS (The Logic)
+// ================================================================
+
+/// Registers the choice and its members, and returns its C enum. The compiler puts it above main.
+pub fn define(
+    tokens: &[Token],
+    symbols: &mut SymbolTable
+) -> Result<String, MommyLangError> {
+    // Syntax: choices <NAME> has <MEMBER>, <MEMBER>, ...
+
+    if tokens.len() <= constants::IDX_RECORD_KEY_HAS + 1 {
+        return Err(MommyLangError::MissingArguments);
+    }
+    if !tokens[constants::IDX_RECORD_KEY_HAS].is_keyword(constants::KW_HAS) {
+        return Err(MommyLangError::SyntaxError);
+    }
+
+    let name = tokens[constants::IDX_RECORD_NAME].as_str();
+    validate_syntax::ensure_valid_name(name)?;
+    validate_syntax::ensure_var_new(name, symbols)?;
+    if validate_syntax::is_builtin_type(name) {
+        return Err(MommyLangError::InvalidVariableName);
+    }
+
+    let joined = syntax_lexer::join(&tokens[constants::IDX_RECORD_KEY_HAS + 1..]);
+    let members: Vec<String> = joined.split(constants::SYM_COMMA).map(|member| member.trim().to_string()).collect();
+
+    for (index, member) in members.iter().enumerate() {
+        if member.is_empty() || member.contains(char::is_whitespace) { // "a, , b" or "a b, c"
+            return Err(MommyLangError::SyntaxError);
+        }
+        validate_syntax::ensure_valid_name(member)?;
+        validate_syntax::ensure_var_new(member, symbols)?;
+        if members[..index].contains(member) || member == name {
+            return Err(MommyLangError::VariableAlreadyExists);
+        }
+    }
+
+    register(name, &members, symbols);
+    Ok(format!("typedef enum {{ {} }} {};", members.join(", "), name))
+}
+
+/// Puts the choice and every one of its members in the table.
+pub fn register(name: &str, members: &[String], symbols: &mut SymbolTable) {
+    symbols.insert(name, Symbol::choice(name, members.to_vec()));
+    for member in members { // "tired" is a mood that never changes
+        symbols.insert(member, Symbol {
+            members: members.to_vec(),
+            constant: Some(member.clone()),
+            mutable: false,
+            ..Symbol::scalar(name)
+        });
+    }
+}
+
+/// A variable, group or heap block of a choice type carries the choice's members. Anything else is left alone.
+pub fn adopt(name: &str, raw_type: &str, symbols: &mut SymbolTable) {
+    let Some(members) = symbols.get(raw_type)
+        .filter(|symbol| symbol.kind == SymbolKind::Choice)
+        .map(|symbol| symbol.members.clone()) else { return };
+    if let Some(symbol) = symbols.get_mut(name) {
+        symbol.members = members;
+    }
+}
+
+/// The member's name as C sees it, so "say today" prints "tired" and not 1.
+pub fn c_name(value: &str, members: &[String]) -> String {
+    let names: Vec<String> = members.iter().map(|member| format!("\"{}\"", member)).collect();
+    format!("((const char*[]){{{}}})[{}]", names.join(", "), value)
+}
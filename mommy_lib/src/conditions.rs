@@ -2,15 +2,22 @@
 //!
 //! This is the condition crate of  mommylang.
 //!
-//! Language Syntax: "ask if var1 comparison_operator var2"
+//! Language Syntax: "ask if condition"
+//! Else-if Syntax: "or ask if condition"
+//! Else Syntax: "or"
 //!
 //! Note:
-//! 1. It can only do one condition at a time, such as "ask if grade > 90"
+//! 1. Conditions can be combined and grouped, such as "ask if grade > 90 and not (late or absent)",
+//!    and both sides of a comparison can be expressions, such as "ask if (a + b) * 2 > limit".
 //!
-//! 2. Logical operators (&&, ||) and parentheses are not supported.
+//! 2. Conditions are checked before we emit C: undeclared variables, comparing words with numbers
+//!    and broken operators like "=" or "=<" are reported here instead of by gcc.
 //!
-//! 3. Boolean is not supported as a type, but you can use 0 and 1 to represent false and true lol.
+//! 3. A chain of "ask", "or ask if" and "or" only needs one "done" at the end.
+//!    "or ask if" after a plain "or" is rejected by the compiler, the plain "or" has to be last.
 //!
+//! 4. A bool works on its own, "ask if finished" or "punishmeif not finished".
+//!    Numbers still work the C way, 0 is false and everything else is true.
 //!
 //!
 //!
@@ -18,23 +25,98 @@
 //!
 //!
 //!
+//!
+use crate::symbol_table::SymbolTable;
 use crate::responses;
 use crate::constants;
+use crate::expression;
+use crate::syntax_lexer::Token;
 
-pub fn ask(tokens: &Vec<String>) -> Result<String, responses::MommyLangError>{
+pub fn ask(tokens: &[Token], symbols: &SymbolTable) -> Result<String, responses::MommyLangError>{
 
     if tokens.len() < constants::ARGS_MIN_COND {
         return Err(responses::MommyLangError::MissingArguments);
     }
 
-    if tokens[constants::IDX_COND_IF] != constants::KW_IF  || tokens[constants::INDX_COND_ASK] != constants::KW_ASK {
+    if !tokens[constants::IDX_COND_IF].is_keyword(constants::KW_IF)  || !tokens[constants::INDX_COND_ASK].is_keyword(constants::KW_ASK) {
         return Err(responses::MommyLangError::SyntaxError);
     }
-    let condition = tokens[2..].join(constants::SYM_WHITESPACE);
-    
+    let condition = expression::compile_condition(&tokens[2..], symbols)?;
+
     Ok(format!("if ({}) {{", condition))
 }
 
-pub fn or() -> Result<String, responses::MommyLangError> {
-    Ok(constants::KW_ELSE_BLOCK.to_string())
-}
\ No newline at end of file
+pub fn or(tokens: &[Token], symbols: &SymbolTable) -> Result<String, responses::MommyLangError> {
+    if tokens.len() == 1 {
+        return Ok(constants::KW_ELSE_BLOCK.to_string());
+    }
+
+    // Syntax: or ask if <CONDITION>
+    let else_if = ask(&tokens[1..], symbols)?;
+    Ok(format!("{}{}", constants::KW_ELSE_IF_PREFIX, else_if))
+}
+
+pub fn is_else_if(tokens: &[Token]) -> bool {
+    tokens.len() > 1
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{declared, tokens};
+
+    #[test]
+    fn or_ask_if_continues_the_chain() {
+        let symbols = declared(&["mayihave 90 in grade as int"]);
+        assert_eq!(or(&tokens("or ask if grade > 80"), &symbols).unwrap(), "}else if (grade > 80) {");
+        assert_eq!(or(&tokens("or"), &symbols).unwrap(), "}else {");
+        assert!(is_else_if(&tokens("or ask if grade > 80")));
+        assert!(!is_else_if(&tokens("or")));
+    }
+
+    #[test]
+    fn or_ask_if_needs_the_whole_phrase() {
+        let symbols = declared(&["mayihave 90 in grade as int"]);
+        assert_eq!(or(&tokens("or if grade > 80"), &symbols), Err(responses::MommyLangError::SyntaxError));
+        assert_eq!(or(&tokens("or ask if"), &symbols), Err(responses::MommyLangError::MissingArguments));
+    }
+
+    #[test]
+    fn bool_is_a_condition_on_its_own() {
+        let symbols = declared(&["mayihave no in finished as bool"]);
+        assert_eq!(ask(&tokens("ask if finished"), &symbols).unwrap(), "if (finished) {");
+        assert_eq!(ask(&tokens("ask if not finished or yes"), &symbols).unwrap(), "if (!finished || 1) {");
+    }
+
+    fn symbols() -> SymbolTable {
+        declared(&["mayihave 90 in grade as int", "mayihave no in late as bool", "mayihave \"Ann\" in name as String"])
+    }
+
+    #[test]
+    fn and_or_not_become_c_logic() {
+        let symbols = symbols();
+        assert_eq!(ask(&tokens("ask if grade >= 90 and not late"), &symbols).unwrap(), "if (grade >= 90 && !late) {");
+        assert_eq!(ask(&tokens("ask if late or grade < 50 and yes"), &symbols).unwrap(), "if (late || grade < 50 && 1) {");
+        assert_eq!(ask(&tokens("ask if not (late or grade == 0)"), &symbols).unwrap(), "if (!(late || grade == 0)) {");
+        assert_eq!(or(&tokens("or ask if grade > 1 && !late"), &symbols).unwrap(), "}else if (grade > 1 && !late) {");
+    }
+
+    #[test]
+    fn text_is_not_compared_with_a_number() {
+        let symbols = symbols();
+        assert_eq!(
+            ask(&tokens("ask if name == 5"), &symbols),
+            Err(responses::MommyLangError::TypeMismatchOn("'name' (String)".to_string(), "'5' (int)".to_string())),
+        );
+        assert!(ask(&tokens("ask if name == \"Ann\""), &symbols).is_ok());
+    }
+
+    #[test]
+    fn malformed_operators_are_rejected() {
+        let symbols = symbols();
+        for line in ["ask if grade = 90", "ask if grade =< 90", "ask if grade > 1 & late", "ask if grade >", "ask if and late"] {
+            assert_eq!(ask(&tokens(line), &symbols), Err(responses::MommyLangError::MalformedOperator), "{}", line);
+        }
+    }
+}
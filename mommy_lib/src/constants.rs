@@ -20,11 +20,21 @@ pub const CMD_RUN_PYTHON: &str = "python";
 pub const CMD_RUN_NOTEPAD: &str = "notepad.exe";
 pub const CMD_CARGO: &str = "cargo";
 
+// Compiler Flags (after the file name)
+pub const FLAG_BOUNDS_CHECK: &str    = "--bounds-check";    // on by default in debug builds
+pub const FLAG_NO_BOUNDS_CHECK: &str = "--no-bounds-check";
+pub const FLAG_TRACK_HEAP: &str      = "--track-heap";      // off unless asked
+pub const FLAG_KEEP_COMMENTS: &str   = "--keep-comments";   // copy "#"/"psst" comments into the C file
+pub const FLAG_MAX_ERRORS: &str      = "--max-errors";      // followed by how many errors one run reports
+
 
 
 // Limits
 pub const ARGS_MIN_MATH: usize = 4;
+pub const ARGS_MIN_CONVERT: usize = 4;  // turn <VALUE> into <TARGET>
+pub const ARGS_MIN_TEXT: usize = 4;     // join <VALUE> onto <TARGET>, measure <TEXT> in <TARGET>
 pub const ARGS_MIN_FILE: usize = 2;
+pub const DEFAULT_MAX_ERRORS: usize = 20; // Errors reported in one run before the compiler stops reading
 pub const ARGS_MIN_LEN: usize  = 2;
 pub const NAME_MIN_LEN: usize  = 3;
 pub const ARGS_MIN_COND: usize = 3;
@@ -45,6 +55,20 @@ pub const KW_ARRAY_DEC: &str  = "group";
 pub const KW_ASSIGN: &str     = "replace";
 pub const KW_MALLOC: &str     = "ibegyou";     // For dynamic memory allocation
 pub const KW_FREE: &str       = "takeitback";     // For freeing dynamic memory
+pub const KW_FUNC_DEC: &str   = "chore";      // Function definition
+pub const KW_RETURN: &str     = "giveback";   // return
+pub const KW_CALL: &str       = "dothis";     // Function call statement
+pub const KW_JOIN: &str       = "join";       // Text concatenation
+pub const KW_MEASURE: &str    = "measure";    // Text length
+pub const KW_SLICE: &str      = "slice";      // Substring
+pub const KW_RECORD: &str     = "record";     // Struct definition
+pub const KW_CHOICES: &str    = "choices";    // Enum definition
+pub const KW_CONSTANT: &str   = "always";     // Constant declaration
+pub const KW_PICK: &str       = "pick";       // switch
+pub const KW_WHEN: &str       = "when";       // case
+pub const KW_OTHERWISE: &str  = "otherwise";  // default
+pub const KW_NAMED: &str      = "named";      // Loop label, "punishme 5 named outer"
+pub const KW_COMMENT: &str    = "psst";       // Comment, the rest of the line is ignored
 
 
 // Connectors
@@ -52,26 +76,49 @@ pub const KW_IN: &str         = "in";
 pub const KW_AS: &str         = "as";
 pub const KW_WITH: &str       = "with";
 pub const KW_UPTO: &str       = "upto";
+pub const KW_INTO: &str       = "into";
+pub const KW_ONTO: &str       = "onto";
+pub const KW_NEEDS: &str      = "needs";
+pub const KW_GIVES: &str      = "gives";
+pub const KW_HAS: &str        = "has";
+pub const KW_FROM: &str       = "from";
+pub const KW_TO: &str         = "to";
+pub const KW_STEP: &str       = "step";
 
 // Pointers & Types
 pub const KW_BOX: &str        = "box";     // Pointer type
 pub const KW_ADDR: &str       = "address"; // & operator
 pub const KW_DEREF: &str      = "inside";  // * operator
 pub const KW_NULL: &str       = "null";
-pub const KW_ARRAY: &str      = "array";   // Internal/Type keyword
 pub const KW_POINTER: &str    = "pointer";
-pub const KW_HEAP: &str       = "heap";    // Heap allocation metadata prefix
 
 // Loop & Logic Keywords
 pub const KW_IF: &str         = "if";
 pub const KW_ELSE_BLOCK: &str = "}else {";
+pub const KW_ELSE_IF_PREFIX: &str = "}else ";
 pub const KW_ASK: &str        = "ask";
+pub const KW_AND: &str        = "and";
+pub const KW_OR: &str         = "or";
+pub const KW_NOT: &str        = "not";
 
 // Types
 pub const TYPE_STRING: &str     = "String";
 pub const TYPE_INT: &str        = "int";
 pub const TYPE_FLOAT: &str      = "float";
 pub const TYPE_ASCII: &str      = "ascii";
+pub const TYPE_BOOL: &str       = "bool";
+pub const TYPE_TEXT: &str       = "text";   // Managed string, lives on the heap
+
+// Bool literals
+pub const KW_YES: &str        = "yes";
+pub const KW_NO: &str         = "no";
+pub const KW_TRUE: &str       = "true";
+pub const KW_FALSE: &str      = "false";
+
+// Math
+pub const KW_DIVIDE: &str     = "divide";
+pub const KW_MOD: &str        = "mod";    // Integers only, like C's %
+pub const KW_CONVERT: &str    = "turn";
 
 // Unique
 
@@ -89,11 +136,25 @@ pub const C_EXIT_SUCC: &str   = "return 0;";
 pub const C_TYPE_CHAR_PTR: &str = "char*";
 pub const C_NULL: &str        = "NULL";
 pub const C_VAL_ZERO: &str    = "0";
+pub const C_VAL_ONE: &str     = "1";
+pub const C_TYPE_BOOL: &str   = "_Bool"; // Built into C99, no header needed
+pub const C_TEXT_PREFIX: &str = "_mommy_text_"; // Every text runtime helper starts with this
+pub const C_GUARD_PREFIX: &str = "_mommy_guard_"; // Every runtime safety check starts with this
+pub const C_SIZE_PREFIX: &str = "_mommy_size_";   // The live size of an "ibegyou" block, "_mommy_size_<NAME>"
 pub const C_TYPE_INT_PTR: &str  = "int*";
 pub const C_KW_RETURN: &str = "return";
 pub const C_KW_VOID: &str   = "void";
 pub const C_KW_WHILE: &str  = "while";
 pub const C_KW_CHAR: &str   = "char";
+pub const C_KW_MAIN: &str   = "main";
+pub const C_KW_SWITCH: &str = "switch";
+pub const C_KW_CASE: &str   = "case";
+pub const C_KW_DEFAULT: &str = "default";
+pub const C_BREAK_BLOCK: &str = "break; }"; // Closes a pick arm
+pub const C_LOOP_EXIT_PREFIX: &str = "_mommy_exit_"; // The label after a loop, for "satisfied" from a pick or an inner loop
+pub const C_LOOP_NEXT_PREFIX: &str = "_mommy_next_"; // The label at the end of a loop body, for "skip <LABEL>"
+pub const C_ROW_PREFIX: &str = "_mommy_row"; // The counters of "say" on a whole group, one per side
+pub const C_RESULT_NAME: &str = "_mommy_result"; // What a chore gives back, held while its texts are freed
 
 // C Operators
 pub const C_OP_ADD: &str      = "+";
@@ -101,13 +162,32 @@ pub const C_OP_SUB: &str      = "-";
 pub const C_OP_DIV: &str      = "/";
 pub const C_OP_MUL: &str      = "*";
 pub const C_OP_MOD: &str      = "%";
-
-// Error Messages / Splitters
-pub const MSG_ERR_LINE: &str  = "Line";
-pub const SYM_SPLITTER: char  = ':';
+pub const C_OP_EQ: &str       = "==";
+pub const C_OP_NEQ: &str      = "!=";
+pub const C_OP_LT: &str       = "<";
+pub const C_OP_GT: &str       = ">";
+pub const C_OP_LTE: &str      = "<=";
+pub const C_OP_GTE: &str      = ">=";
+pub const C_OP_AND: &str      = "&&";
+pub const C_OP_OR: &str       = "||";
+pub const C_OP_NOT: &str      = "!";
+
+// Splitters
 pub const SYM_SLASH: char     = '/';
 pub const SYM_BACKSLASH: char = '\\';
 pub const SYM_WHITESPACE: &str = " ";
+pub const SYM_COMMA: &str     = ",";
+pub const SYM_DOT: char       = '.';
+pub const SYM_COMMENT: char   = '#';    // Short comment, same as "psst"
+pub const C_COMMENT_PREFIX: &str = "// ";
+
+// Diagnostics (error[E007]: ... / --> file:3:9 / caret under the token)
+pub const DIAG_ERROR: &str    = "error";
+pub const DIAG_WARNING: &str  = "warning";
+pub const DIAG_ARROW: &str    = "--> ";
+pub const DIAG_GUTTER: &str   = "|";
+pub const DIAG_HELP: &str     = "= help: ";
+pub const SYM_CARET: char     = '^';
 
 // ================================================================
 // 4. PARSING INDICES (The "Slots")
@@ -125,6 +205,10 @@ pub const IDX_DECL_NAME: usize      = 3;
 pub const IDX_DECL_KEY_AS: usize    = 4; // "as"
 pub const IDX_DECL_TYPE: usize      = 5;
 
+// Heap Resize: ibegyoumore <SIZE> in <NAME>
+// Indices:     0           1      2  3
+pub const ARGS_RESIZE: usize        = 4;
+
 // Assignment: replace <NAME> with <VAL>
 // Indices:    0       1      2    3
 pub const ARGS_MIN_ASSIGN: usize    = 4;
@@ -144,6 +228,17 @@ pub const IDX_ARR_KEY_WITH: usize   = 4; // "with"
 pub const IDX_ARR_VALUE: usize      = 5;
 pub const IDX_ARR_NAME: usize       = 1;
 
+// Array Read: replace <DEST> with <ARRAY> in <IDX>
+// Indices:    0       1      2    3       4  5
+pub const ARGS_ARR_READ: usize      = 6;
+pub const IDX_ARR_READ_SOURCE: usize= 3;
+pub const IDX_ARR_READ_INDEX: usize = 5;
+
+// Array Initializer: group <SIZE> in <NAME> as <TYPE> with <VALUES>
+// Indices:           0     1      2  3      4  5      6    7..
+pub const IDX_ARR_INIT_KEY_WITH: usize = 6; // "with"
+pub const IDX_ARR_INIT_VALUES: usize   = 7;
+
 // Math: add <TARGET> with <SOURCE>
 // Indices: 0   1        2    3
 pub const IDX_MATH_TARGET: usize    = 1;
@@ -156,18 +251,31 @@ pub const IDX_IO_VALUE: usize   = 1; // The thing being said
 pub const IDX_IO_KEY_IN: usize  = 2; // "in" keyword
 pub const IDX_IO_ARR_IDX: usize = 3; // The array index
 
-// Loops: punishme <COUNT>
+// Loops: punishme <COUNT> [as <NAME>]
+// Range:  punishme <NAME> from <START> to <END> [step <STEP>]
 pub const IDX_LOOP_COUNT: usize = 1;
+pub const IDX_LOOP_KEY_FROM: usize = 2; // "from"
+pub const LOOP_DEFAULT_STEP: &str = "1";
+pub const LOOP_COUNTER_PREFIX: &str = "_mommy_loop_";
+
+// Chores: chore <NAME> needs <P> as <TYPE>, ... gives <TYPE>
+// Calls:  dothis <NAME> with <ARG>, ...
+pub const IDX_FUNC_NAME: usize      = 1;
+pub const IDX_FUNC_KEY_NEEDS: usize = 2; // "needs"
+pub const IDX_CALL_KEY_WITH: usize  = 2; // "with"
+
+// Records: record <NAME> has <FIELD> as <TYPE>, ...
+// Fields:  <VAR>.<FIELD>, <ARRAY> in <INDEX>.<FIELD>
+pub const IDX_RECORD_NAME: usize    = 1;
+pub const IDX_RECORD_KEY_HAS: usize = 2; // "has"
+// Choices use the same positions: choices <NAME> has <MEMBER>, ...
 
 // Conditions: ask if <VAR1> <COMP_OP> <VAR2>
 pub const IDX_COND_IF: usize = 1;
-pub const INDX_COND_ASK: usize = 0;
 
-// Metadata or symbol : type_indicator:data_type:size
-// This is only for heap and array for now
-pub const IDX_META_TYPE_INDICATOR: usize = 0;
-pub const IDX_META_DATA_TYPE: usize = 1;
-pub const IDX_META_SIZE: usize = 2;
+// Picks: "pick <VALUE>", "when <VALUE>, ..."
+pub const IDX_PICK_VALUE: usize = 1;
+pub const INDX_COND_ASK: usize = 0;
 
 
 // ================================================================
@@ -4,21 +4,37 @@
 //! Language syntax:
 //! - Scalar variables: "mayihave value in var_name as type"
 //! - Arrays: "group size in var_name as type"
+//! - Grids: "group rows,columns in var_name as type", written and read with "var_name in row,column"
+//! - Arrays with values: "group 5 in var_name as int with 1, 2, 3" or "group 20 in var_name as ascii with "HELLO""
+//! - Constants: "always value in var_name as type", never changed again
 //! - Heap allocation: "ibegyou size in var_name as type"
+//! - Heap resize: "ibegyoumore size in var_name"
 //! - Heap deallocation: "takeitback var_name"
 //! - Reassignment: "replace var_name with new_value"
 //! - Array write: "replace var_name in index with new_value"
 //! - Array read: "replace var_name with array_name in index"
+//! - Record fields: "replace var_name.field with new_value", "replace array_name in index.field with new_value"
 //! - Pointer address: "replace var_name with other_var address"
 //! - Pointer dereference write: "replace var_name with new_value inside"
 //!
 //! Notes:
-//! 1) Float, integer, and ASCII (unique int for string) types are supported.
+//! 1) Float, integer, bool (yes/no), text, and ASCII (unique int for string) types are supported.
+//! 2) With bounds checks on, a variable index is checked while the program runs. A bad one names
+//!    the .mommy line and exits with code 3. Literal indices are already checked here.
+//! 3) Every heap block keeps its live size next to it in C ("_mommy_size_var_name"), so resizing,
+//!    "measure" and the bounds checks always see the real size.
+//! 4) An array initializer has to fit the declared size, the slots that are left over are 0.
+//!    A string literal only fills ascii and char arrays, one letter per slot. An escape like "\n" is one letter.
+//! 5) A constant can size a group or heap block ("group max in scores as int"). Its value is written
+//!    into the C, so the size is still a plain number there.
+//! 6) A heap block needs at least one slot. A fixed size below 1 is rejected here, a variable one
+//!    is checked while the program runs, which names the .mommy line and exits with code 5.
 //!
 //! About the string implementation:
 //! I avoid using char* directly because it is complex to manage at this stage.
 //! Instead, strings are represented as arrays of ASCII integers. This keeps the
 //! syntax simple for users while still letting the compiler emit valid C code.
+//! If you want a string that can grow, use "text", it is managed by the text crate.
 //!
 //!
 //!
@@ -26,25 +42,32 @@
 //!
 
 
-use std::collections::HashMap;
+use crate::symbol_table::{Symbol, SymbolTable};
 use crate::responses::MommyLangError;
 use crate::constants;
+use crate::choice;
+use crate::expression::{self, Expr};
+use crate::lang_enums::SymbolKind;
+use crate::lifetime;
+use crate::record;
+use crate::text;
 use crate::validate_syntax;
+use crate::syntax_lexer::{self, Token};
 
 // ================================================================
 // PUBLIC FUNCTIONS (The Logic)
 // ================================================================
 
 pub fn create_variable(
-    tokens: &Vec<String>,
-    symbols: &mut HashMap<String, String>
+    tokens: &[Token],
+    symbols: &mut SymbolTable
 ) -> Result<String, MommyLangError> {
 
     if  validate_syntax::is_missing_decl_args(tokens.len()){
         return Err(MommyLangError::MissingArguments);
     }
 
-    let in_index = tokens.iter().position(|r| r == constants::KW_IN) // in
+    let in_index = tokens.iter().position(|r| r.is_keyword(constants::KW_IN)) // in
         .ok_or(MommyLangError::SyntaxError)?;
 
     let name_index = in_index + 1;
@@ -54,67 +77,171 @@ pub fn create_variable(
         return Err(MommyLangError::SyntaxError);
     }
 
-    let name = &tokens[name_index];
-    let raw_type = &tokens[type_index];
+    let name = tokens[name_index].as_str();
+    let raw_type = tokens[type_index].as_str();
 
     validate_syntax::ensure_valid_name(name)?;
     validate_syntax::ensure_var_new(name, symbols)?;
 
+    if record::is_record_type(raw_type, symbols) { // "mayihave null in ann as student"
+        return record::declare(name, raw_type, &tokens[1..in_index], symbols);
+    }
+
     let c_type = get_c_type(raw_type);
 
-    insert_symbol(raw_type, name, symbols);
+    // The value is checked before the name exists, so "mayihave x + 1 in x as int" is rejected
+    let value = c_value(&tokens[1..in_index], symbols)?;
 
-    let value_tokens = &tokens[1..in_index];
-    let mut value = value_tokens.join(constants::SYM_WHITESPACE);
+    insert_symbol(raw_type, name, symbols);
+    choice::adopt(name, raw_type, symbols);
 
-    value = c_null(value.as_str());
+    if raw_type == constants::TYPE_TEXT { // Our own copy, never the literal itself
+        return Ok(text::declare(name, &value));
+    }
 
     Ok(format!("{} {} = {};", c_type, name, value))
 }
 
+/// Named constants: "always 10 in max as int". Declared like "mayihave", but the value is fixed for good.
+pub fn create_constant(
+    tokens: &[Token],
+    symbols: &mut SymbolTable
+) -> Result<String, MommyLangError> {
+    // Syntax: always <VALUE> in <NAME> as <TYPE>
+
+    let in_index = tokens.iter().position(|r| r.is_keyword(constants::KW_IN))
+        .ok_or(MommyLangError::SyntaxError)?;
+    if let Some(raw_type) = tokens.get(in_index + 3).map(Token::as_str)
+        && (raw_type == constants::TYPE_TEXT || record::is_record_type(raw_type, symbols)) {
+        return Err(MommyLangError::TypeMismatch); // Those only make sense when they can change
+    }
+
+    // Fixed first, so a value that is not leaves no name behind that could still be changed
+    let value = fixed_value(&tokens[1..in_index], symbols).ok_or(MommyLangError::ConstantNotFixed)?;
+    create_variable(tokens, symbols)?; // Name, type and value, the same checks as "mayihave"
+
+    let name = tokens[in_index + 1].as_str();
+    let c_type = get_c_type(tokens[in_index + 3].as_str());
+    if let Some(symbol) = symbols.get_mut(name) {
+        symbol.mutable = false;
+        symbol.constant = Some(value.clone());
+    }
+    // The value itself, not the name it came from: C only takes plain values outside of main
+    Ok(format!("const {} {} = {};", c_type, name, value))
+}
+
 
 
 pub fn create_array(
-    tokens: &Vec<String>,
-    symbols: &mut HashMap<String, String>
+    tokens: &[Token],
+    symbols: &mut SymbolTable
 ) -> Result<String, MommyLangError> {
 
     if validate_syntax::is_missing_decl_args(tokens.len()){
         return Err(MommyLangError::MissingArguments);
     }
 
-    let size_str = &tokens[constants::IDX_DECL_VALUE];
-    let name     = &tokens[constants::IDX_DECL_NAME];
-    let raw_type = &tokens[constants::IDX_DECL_TYPE];
+    let size_str = tokens[constants::IDX_DECL_VALUE].as_str();
+    let name     = tokens[constants::IDX_DECL_NAME].as_str();
+    let raw_type = tokens[constants::IDX_DECL_TYPE].as_str();
 
     validate_syntax::ensure_valid_name(name)?;
     validate_syntax::ensure_var_new(name, symbols)?;
 
-    if validate_syntax::is_invalid_array_tokens(&tokens){
+    if validate_syntax::is_invalid_array_tokens(tokens){
         return Err(MommyLangError::SyntaxError);
     }
 
-    if validate_syntax::is_invalid_array_size(size_str.as_str()){
+    // Every side of a grid follows the same rule as a plain group, constants count as their value
+    let sides: Vec<String> = split_index(size_str).into_iter().map(|side| resolve_constant(side, symbols)).collect();
+    if sides.iter().any(|side| validate_syntax::is_invalid_array_size(side)) {
         return Err(MommyLangError::SyntaxError);
     }
+    let dims: Vec<usize> = sides.iter().filter_map(|side| side.parse().ok()).collect();
+    let total: usize = dims.iter().product(); // A grid is filled row after row
+
+    // Optional: with <VALUES>
+    let initializer = match tokens.get(constants::IDX_ARR_INIT_KEY_WITH) {
+        None => "{0}".to_string(),
+        Some(t) if t.is_keyword(constants::KW_WITH) && record::is_record_type(raw_type, symbols) => return Err(MommyLangError::TypeMismatch),
+        Some(t) if t.is_keyword(constants::KW_WITH) && tokens.len() > constants::IDX_ARR_INIT_VALUES =>
+            array_initializer(raw_type, &total.to_string(), &tokens[constants::IDX_ARR_INIT_VALUES..], symbols)?,
+        Some(t) if t.is_keyword(constants::KW_WITH) => return Err(MommyLangError::MissingArguments),
+        Some(_) => return Err(MommyLangError::SyntaxError),
+    };
 
-    let meta = format!("{}:{}:{}", constants::KW_ARRAY, raw_type, size_str);
-    symbols.insert(name.to_string(), meta);
+    let c_sides: String = sides.iter().map(|side| format!("[{}]", side)).collect();
+    if dims.len() > 1 {
+        symbols.insert(name, Symbol::grid(raw_type, dims));
+    } else {
+        symbols.insert(name, Symbol::array(raw_type, &sides[0]));
+    }
+    record::adopt(name, raw_type, symbols);
+    choice::adopt(name, raw_type, symbols);
 
-    let c_type = match raw_type.as_str(){
+    let c_type = match raw_type{
         constants::TYPE_ASCII => constants::TYPE_INT,
-        _ => get_c_type(&raw_type),
+        _ => get_c_type(raw_type),
     };
 
-    Ok(format!("{} {}[{}] = {{0}};", c_type, name, size_str))
+    Ok(format!("{} {}{} = {};", c_type, name, c_sides, initializer))
+}
+
+/// Splits "1, 2, fib(3, 4)" or ""a, b", "c"" into its values. Commas inside quotes, chars or brackets stay put.
+/// Every value points at the token it started in.
+pub fn split_initializer(tokens: &[Token]) -> Vec<Token> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut started_at: Option<&Token> = None;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut depth = 0;
+
+    for token in tokens {
+        if !current.is_empty() {
+            current.push_str(constants::SYM_WHITESPACE);
+        }
+        for c in token.text.chars() {
+            match c {
+                _ if escaped => escaped = false,
+                constants::SYM_BACKSLASH if quote.is_some() => escaped = true,
+                '"' | '\'' if quote.is_none() => quote = Some(c),
+                _ if quote == Some(c) => quote = None,
+                '(' if quote.is_none() => depth += 1,
+                ')' if quote.is_none() => depth -= 1,
+                ',' if quote.is_none() && depth == 0 => {
+                    values.push(value_token(&current, started_at.unwrap_or(token)));
+                    current.clear();
+                    started_at = None;
+                    continue;
+                }
+                _ => {}
+            }
+            started_at.get_or_insert(token);
+            current.push(c);
+        }
+    }
+    let span = started_at.or(tokens.last()).map(|token| token.span).unwrap_or_default();
+    values.push(Token::new(current.trim(), span));
+    values
+}
+
+/// A string literal, when it is the whole initializer of an ascii or char array. Escapes are one letter each.
+pub fn initializer_letters(raw_type: &str, values: &[Token]) -> Option<String> {
+    let [value] = values else { return None };
+    if !matches!(raw_type, constants::TYPE_ASCII | constants::C_KW_CHAR) || value.text.len() < 2 {
+        return None;
+    }
+    let inner = value.text.strip_prefix('"')?.strip_suffix('"')?;
+    Some(syntax_lexer::unescape(inner))
 }
 
 
 
 /// Heap Allocation
 pub fn allocate_heap(
-    tokens: &Vec<String>,
-    symbols: &mut HashMap<String, String>
+    tokens: &[Token],
+    symbols: &mut SymbolTable
 ) -> Result<String, MommyLangError> {
     // Syntax: ibegyou <SIZE> in <NAME> as <TYPE>
 
@@ -122,59 +249,127 @@ pub fn allocate_heap(
         return Err(MommyLangError::MissingArguments);
     }
 
-    let size_val = &tokens[constants::IDX_DECL_VALUE];
-    let name = &tokens[constants::IDX_DECL_NAME];
-    let raw_type = &tokens[constants::IDX_DECL_TYPE];
+    let size_val = &resolve_constant(tokens[constants::IDX_DECL_VALUE].as_str(), symbols);
+    let name = tokens[constants::IDX_DECL_NAME].as_str();
+    let raw_type = tokens[constants::IDX_DECL_TYPE].as_str();
 
-    if validate_syntax::is_invalid_array_tokens(&tokens){
+    if validate_syntax::is_invalid_array_tokens(tokens){
         return Err(MommyLangError::SyntaxError);
     }
 
     validate_syntax::ensure_valid_name(name)?;
     validate_syntax::ensure_var_new(name, symbols)?;
 
-    let c_type = match raw_type.as_str() {
+    let c_type = match raw_type {
         constants::TYPE_ASCII => constants::TYPE_INT,
         _ => get_c_type(raw_type),
     };
 
-    let meta = format!("heap:{}:{}", raw_type, size_val);
-    symbols.insert(name.to_string(), meta);
-
-    let c_code = format!(
-        "{0}* {1} = ({0}*)malloc({2} * sizeof({0})); \
+    // A literal size is still checked here, a variable one only while the program runs
+    let size = heap_size(&tokens[constants::IDX_DECL_VALUE], name, symbols)?;
+    let size_name = heap_size_name(name);
+    let known_size = if size_val.parse::<usize>().is_ok() { size_val } else { &size_name };
+    symbols.insert(name, Symbol::heap(raw_type, known_size));
+    record::adopt(name, raw_type, symbols);
+    choice::adopt(name, raw_type, symbols);
+
+    // The size is worked out once, before the block, so a bad one stops the program before malloc sees it
+    let mut c_code = format!(
+        "{3} {4} = {2}; \
+        {0}* {1} = ({0}*)malloc({4} * sizeof({0})); \
         if ({1} == NULL) {{ \
-        printf(\"Mommy Error: No memory for {1}\\n\"); return 1; }}",
+        printf(\"Mommy Error: No memory for {1}\\n\"); exit(1); }}",
         c_type,
         name,
-        size_val
+        size,
+        constants::TYPE_INT,
+        size_name
     );
 
+    if symbols.track_heap() {
+        c_code.push_str(&format!(" {}track({}, \"{}\", {});", constants::C_GUARD_PREFIX, name, name, symbols.line()));
+    }
 
     Ok(c_code)
 }
 
-pub fn deallocate_heap( tokens: &Vec<String>,
-    symbols: &mut HashMap<String, String>
+/// Heap Resize
+pub fn resize_heap(
+    tokens: &[Token],
+    symbols: &mut SymbolTable
+) -> Result<String, MommyLangError> {
+    // Syntax: ibegyoumore <SIZE> in <NAME>
+
+    if tokens.len() < constants::ARGS_RESIZE {
+        return Err(MommyLangError::MissingArguments);
+    }
+    if tokens.len() > constants::ARGS_RESIZE || !tokens[constants::IDX_DECL_KEY_IN].is_keyword(constants::KW_IN) {
+        return Err(MommyLangError::SyntaxError);
+    }
+
+    let name = tokens[constants::IDX_DECL_NAME].as_str();
+
+    let symbol = symbols.lookup(name)?;
+    if symbol.kind != SymbolKind::Heap {
+        return Err(MommyLangError::NotHeapMemory);
+    }
+    let begged_on = symbol.line; // A leak is reported where the block was begged for, not where it grew
+    let size = heap_size(&tokens[constants::IDX_DECL_VALUE], name, symbols)?; // Not 0 either, use "takeitback" for that
+
+    let c_type = match symbol.data_type.as_str() {
+        constants::TYPE_ASCII => constants::TYPE_INT,
+        t => get_c_type(t),
+    };
+    let size_name = heap_size_name(name);
+
+    // realloc keeps the old block if it fails, so we only take the new one when it worked
+    let mut c_code = format!(
+        "{{ int _mommy_wanted = {2}; \
+        {0}* _mommy_grown = ({0}*)realloc({1}, _mommy_wanted * sizeof({0})); \
+        if (_mommy_grown == NULL) {{ \
+        printf(\"Mommy Error: No more memory for {1}\\n\"); exit(1); }} \
+        {1} = _mommy_grown; {3} = _mommy_wanted; }}",
+        c_type,
+        name,
+        size,
+        size_name
+    );
+
+    if symbols.track_heap() { // The block might have moved
+        let guard = constants::C_GUARD_PREFIX;
+        c_code = format!("{}untrack({}); {} {}track({}, \"{}\", {});", guard, name, c_code, guard, name, name, begged_on);
+    }
+
+    if let Some(symbol) = symbols.get_mut(name) {
+        symbol.size = Some(size_name); // From now on only the program knows the size
+    }
+    Ok(c_code)
+}
+
+pub fn deallocate_heap( tokens: &[Token],
+    symbols: &mut SymbolTable
 ) -> Result<String, MommyLangError> {
     // Syntax: takeitback <NAME>
 
-    if validate_syntax::is_invalid_dealloc_tokens(&tokens){
+    if validate_syntax::is_invalid_dealloc_tokens(tokens){
         return Err(MommyLangError::MissingArguments);
     }
 
-    let name = &tokens[constants::IDX_DECL_VALUE];
+    let name = tokens[constants::IDX_DECL_VALUE].as_str();
 
-    validate_syntax::ensure_var_exists(name, symbols)?;
+    lifetime::release(name, symbols)?; // Only live "ibegyou" blocks, and only once
 
+    if symbols.track_heap() {
+        return Ok(format!("{}untrack({}); free({}); {} = NULL;", constants::C_GUARD_PREFIX, name, name, name));
+    }
     Ok(format!("free({}); {} = NULL;", name, name))
 }
 
 
 
 pub fn replace(
-    tokens: &Vec<String>,
-    symbols: &mut HashMap<String, String>
+    tokens: &[Token],
+    symbols: &mut SymbolTable
 ) -> Result<String, MommyLangError> {
 
     if tokens.len() < constants::ARGS_MIN_ASSIGN {
@@ -182,19 +377,19 @@ pub fn replace(
     }
 
     // Write
-    if tokens[constants::IDX_ARR_KEY_IN] == constants::KW_IN {
+    if tokens[constants::IDX_ARR_KEY_IN].is_keyword(constants::KW_IN) {
         return replace_array_write(tokens, symbols);
     }
 
     // Read
     if tokens.len() >= constants::ARGS_MIN_ARR_ASSIGN
-        && tokens[constants::IDX_ASSIGN_KEY_WITH] == constants::KW_WITH
-        && tokens[constants::IDX_ARR_KEY_WITH] == constants::KW_IN {
+        && tokens[constants::IDX_ASSIGN_KEY_WITH].is_keyword(constants::KW_WITH)
+        && tokens[constants::IDX_ARR_KEY_WITH].is_keyword(constants::KW_IN) {
         return replace_array_read(tokens, symbols);
     }
 
     // Scalar Value or Pointer
-    if tokens[constants::IDX_ASSIGN_KEY_WITH] == constants::KW_WITH {
+    if tokens[constants::IDX_ASSIGN_KEY_WITH].is_keyword(constants::KW_WITH) {
         return replace_scalar_value(tokens, symbols);
     }
 
@@ -206,94 +401,108 @@ pub fn replace(
 // ================================================================
 
 fn replace_array_write(
-    tokens: &Vec<String>,
-    symbols: &mut HashMap<String, String>
+    tokens: &[Token],
+    symbols: &mut SymbolTable
 ) -> Result<String, MommyLangError> {
     // Syntax: replace [NAME] in [IDX] with [VAL]
-    if tokens.len() < constants::ARGS_MIN_ARR_ASSIGN || tokens[constants::IDX_ARR_KEY_WITH] != constants::KW_WITH {
+    if tokens.len() < constants::ARGS_MIN_ARR_ASSIGN || !tokens[constants::IDX_ARR_KEY_WITH].is_keyword(constants::KW_WITH) {
         return Err(MommyLangError::SyntaxError);
     }
 
-    let name  = &tokens[constants::IDX_ARR_NAME];
-    let index = &tokens[constants::IDX_ARR_INDEX];
-    let value = &tokens[constants::IDX_ARR_VALUE];
+    let name  = tokens[constants::IDX_ARR_NAME].as_str();
+    let index = tokens[constants::IDX_ARR_INDEX].as_str();
+    let value = c_value(&tokens[constants::IDX_ARR_VALUE..], symbols)?; // "yes" has to become 1, "a + 10" is one value
 
-    validate_syntax::ensure_var_exists(name, symbols)?;
-    let var_type = symbols.get(name).ok_or(MommyLangError::UndeclaredVariable)?;
+    let symbol = symbols.lookup(name)?;
+    validate_syntax::ensure_mutable(symbol)?;
 
-    if validate_syntax::is_type_mismatch(var_type.as_str()){
+    if validate_syntax::is_type_mismatch(symbol){
         return Err(MommyLangError::TypeMismatch);
     }
 
-    if validate_syntax::is_accessed_index_invalid(var_type.as_str(), index){
+    if validate_syntax::is_accessed_index_invalid(symbol, index){
         return Err(MommyLangError::AccessViolation);
     }
 
-    Ok(format!("{}[{}] = {};", name, index, value))
+    Ok(format!("{} = {};", c_element(name, symbol, index, symbols)?, value))
 }
 
 fn replace_array_read(
-    tokens: &Vec<String>,
-    symbols: &mut HashMap<String, String>
+    tokens: &[Token],
+    symbols: &mut SymbolTable
 ) -> Result<String, MommyLangError> {
     // Syntax: replace [DEST] with [SRC] in [IDX]
-    let dest_var  = &tokens[1];
-    let src_array = &tokens[3];
-    let index     = &tokens[5];
+    if tokens.len() > constants::ARGS_ARR_READ { // "xs in 0 + 5" could be xs[0] + 5 or xs[5], we do not guess
+        return Err(MommyLangError::SyntaxError);
+    }
 
-    let array_type = symbols.get(src_array).ok_or(MommyLangError::UndeclaredVariable)?;
+    let dest_var  = tokens[constants::IDX_ASSIGN_NAME].as_str();
+    let src_array = tokens[constants::IDX_ARR_READ_SOURCE].as_str();
+    let index     = tokens[constants::IDX_ARR_READ_INDEX].as_str();
 
-    if validate_syntax::is_type_mismatch(array_type.as_str()){
+    validate_syntax::ensure_mutable(symbols.lookup(dest_var)?)?;
+    let array = symbols.lookup(src_array)?;
+
+    if validate_syntax::is_type_mismatch(array){
         return Err(MommyLangError::TypeMismatch);
     }
 
-    if validate_syntax::is_accessed_index_invalid(array_type.as_str(), index){
+    if validate_syntax::is_accessed_index_invalid(array, index){
         return Err(MommyLangError::AccessViolation);
     }
 
-    Ok(format!("{} = {}[{}];", dest_var, src_array, index))
+    Ok(format!("{} = {};", dest_var, c_element(src_array, array, index, symbols)?))
 }
 
 fn replace_scalar_value(
-    tokens: &Vec<String>,
-    symbols: &mut HashMap<String, String>
+    tokens: &[Token],
+    symbols: &mut SymbolTable
 ) -> Result<String, MommyLangError> {
-    // Syntax: replace <NAME> with <VALUE>
+    // Syntax: replace <NAME> with <VALUE/EXPRESSION>
 
-    let name  = &tokens[constants::IDX_ASSIGN_NAME];
-    let value = &tokens[constants::IDX_ASSIGN_VALUE];
+    let name  = tokens[constants::IDX_ASSIGN_NAME].as_str();
+    let value = tokens[constants::IDX_ASSIGN_VALUE].as_str();
+    let value_tokens = &tokens[constants::IDX_ASSIGN_VALUE..];
 
-    if validate_syntax::is_keyword_with_missing(&tokens){
+    if validate_syntax::is_keyword_with_missing(tokens){
         return Err(MommyLangError::SyntaxError);
     }
 
-    validate_syntax::ensure_var_exists(name, symbols)?;
+    let symbol = symbols.lookup(name)?;
+    validate_syntax::ensure_mutable(symbol)?;
 
-    let var_type = symbols.get(name).ok_or(MommyLangError::UndeclaredVariable)?;
     let last_token = tokens.last().ok_or(MommyLangError::SyntaxError)?;
 
-    if validate_syntax::is_replace_pointer(last_token.as_str()){
+    if validate_syntax::is_replace_pointer(last_token){
         validate_syntax::ensure_var_exists(value, symbols)?;
         return Ok(format!("{} = &{};", name, value));
     }
 
 
-    if validate_syntax::is_deref_assignment(last_token.as_str()){
-        if validate_syntax::is_type_pointer(var_type.as_str()){
+    if validate_syntax::is_deref_assignment(last_token){
+        if validate_syntax::is_type_pointer(symbol){
+            let value = c_value(&value_tokens[..value_tokens.len() - 1], symbols)?;
              return Ok(format!(
-                "if ({0} == NULL) {{ printf(\"Mommy Error: NULL Pointer access on '{0}'\\n\"); return 1; }} *{0} = {1};",
+                "if ({0} == NULL) {{ printf(\"Mommy Error: NULL Pointer access on '{0}'\\n\"); exit(1); }} *{0} = {1};",
                 name, value
             ));
         }
+         validate_syntax::ensure_var_exists(value, symbols)?;
          return Ok(format!("{} = *{};", name, value));
     }
 
+    let value = c_value(value_tokens, symbols)?;
+    if text::is_managed(symbol) {
+        return Ok(text::assign(name, &value));
+    }
     Ok(format!("{} = {};", name, value))
 }
 
-fn get_c_type(raw_type: &str) -> &str {
+pub fn get_c_type(raw_type: &str) -> &str {
     match raw_type {
-        constants::TYPE_STRING => constants::C_TYPE_CHAR_PTR,
+        constants::TYPE_STRING | constants::TYPE_TEXT => constants::C_TYPE_CHAR_PTR,
+        constants::TYPE_ASCII => constants::TYPE_INT,
+        constants::TYPE_BOOL => constants::C_TYPE_BOOL,
 
         // FIX: Match BOTH the user keyword ("box") and internal name ("pointer")
         t if t == constants::KW_BOX || t == constants::KW_POINTER => constants::C_TYPE_INT_PTR,
@@ -303,12 +512,155 @@ fn get_c_type(raw_type: &str) -> &str {
 }
 
 
-pub fn insert_symbol(raw_type: &str, name: &str, symbols: &mut HashMap<String, String>) {
-    if raw_type == constants::KW_BOX {
-        symbols.insert(name.to_string(), constants::KW_POINTER.to_string());
+pub fn insert_symbol(raw_type: &str, name: &str, symbols: &mut SymbolTable) {
+    if raw_type == constants::KW_BOX || raw_type == constants::KW_POINTER {
+        symbols.insert(name, Symbol::pointer());
     } else {
-        symbols.insert(name.to_string(), raw_type.to_string());
+        symbols.insert(name, Symbol::scalar(raw_type));
+    }
+}
+
+/// String literals and null go through untouched, everything else is an expression.
+pub fn c_value(value_tokens: &[Token], symbols: &SymbolTable) -> Result<String, MommyLangError> {
+    let raw = syntax_lexer::join(value_tokens);
+
+    if syntax_lexer::is_text_literal(value_tokens) || raw == constants::KW_NULL {
+        return Ok(c_null(raw.as_str()));
     }
+
+    Ok(expression::parse_value(value_tokens, symbols)?.to_string())
+}
+
+fn array_initializer(
+    raw_type: &str,
+    size_str: &str,
+    tokens: &[Token],
+    symbols: &SymbolTable
+) -> Result<String, MommyLangError> {
+    let values = split_initializer(tokens);
+    let size = size_str.parse::<usize>().map_err(|_| MommyLangError::InvalidArraySize)?;
+
+    // "HELLO" becomes {72, 69, 76, 76, 79}
+    let c_values = match initializer_letters(raw_type, &values) {
+        Some(letters) => letters.chars().map(|c| (c as u32).to_string()).collect(),
+        None => {
+            let mut c_values = Vec::new();
+            for value in &values {
+                if value.text.is_empty() { // "1, , 2"
+                    return Err(MommyLangError::SyntaxError);
+                }
+                c_values.push(c_value(std::slice::from_ref(value), symbols)?);
+            }
+            c_values
+        }
+    };
+
+    if c_values.len() > size || c_values.len() > constants::MAX_ARRAY_SIZE {
+        return Err(MommyLangError::InitializerTooLong);
+    }
+    if c_values.is_empty() {
+        return Ok("{0}".to_string());
+    }
+    Ok(format!("{{{}}}", c_values.join(", ")))
+}
+
+/// "name in 1,2" as C sees it, "name[1][2]". One index per side, and maybe a field ("name in 2.grade").
+pub fn c_element(name: &str, array: &Symbol, index: &str, symbols: &SymbolTable) -> Result<String, MommyLangError> {
+    let (index, field) = record::split_field(index);
+    let parts = split_index(index);
+    if parts.len() != array_sides(array).len() {
+        return Err(MommyLangError::WrongIndexCount);
+    }
+    record::c_field(c_element_prefix(name, array, &parts, symbols)?, array, field)
+}
+
+/// Like `c_element`, but fewer indices than sides are fine, "board in 1" is a whole row.
+/// Literal indices are checked here. A variable one is wrapped in a runtime check when bounds checks are on.
+pub fn c_element_prefix(name: &str, array: &Symbol, parts: &[&str], symbols: &SymbolTable) -> Result<String, MommyLangError> {
+    let sides = array_sides(array);
+    if parts.len() > sides.len() {
+        return Err(MommyLangError::WrongIndexCount);
+    }
+
+    let mut element = name.to_string();
+    for (part, side) in parts.iter().zip(&sides) {
+        let part = resolve_constant(part, symbols);
+        let literal = part.parse::<usize>().ok();
+        if let (Some(idx), Some(max)) = (literal, side.as_ref().and_then(|side| side.parse::<usize>().ok()))
+            && idx >= max {
+            return Err(MommyLangError::AccessViolation);
+        }
+
+        match side {
+            Some(side) if symbols.bounds_check() && literal.is_none() => element.push_str(&format!(
+                "[{}index({}, {}, {})]", constants::C_GUARD_PREFIX, part, side, symbols.line())),
+            _ => element.push_str(&format!("[{}]", part)),
+        }
+    }
+    Ok(element)
+}
+
+/// The size of every side, as C sees it. A String or box has one side we know nothing about.
+pub fn array_sides(array: &Symbol) -> Vec<Option<String>> {
+    if array.is_grid() {
+        return array.dims.iter().map(|side| Some(side.to_string())).collect();
+    }
+    vec![c_length(array).map(str::to_string)]
+}
+
+/// "1,2" is two indices, "i" is one.
+pub fn split_index(index: &str) -> Vec<&str> {
+    index.split(constants::SYM_COMMA).collect()
+}
+
+/// How many slots a group or heap block has, as C sees it: "5", or "_mommy_size_buffer" for a heap block
+/// whose size is only known while the program runs.
+pub fn c_length(array: &Symbol) -> Option<&str> {
+    if !array.is_indexable() {
+        return None;
+    }
+    array.size.as_deref()
+}
+
+/// A constant's value in place of its name ("max" becomes "10"), so it can size a group. Anything else stays.
+pub fn resolve_constant(token: &str, symbols: &SymbolTable) -> String {
+    symbols.get(token)
+        .and_then(|symbol| symbol.constant.clone())
+        .unwrap_or_else(|| token.to_string())
+}
+
+/// The value of a constant, known before the program runs: a literal, or another constant.
+pub fn fixed_value(value_tokens: &[Token], symbols: &SymbolTable) -> Option<String> {
+    if syntax_lexer::is_text_literal(value_tokens) {
+        return Some(syntax_lexer::join(value_tokens));
+    }
+
+    let value = expression::parse_value(value_tokens, symbols).ok()?;
+    match &value {
+        Expr::Variable(name) => symbols.get(name)?.constant.clone(),
+        Expr::Bool(_) | Expr::Null => Some(value.to_string()),
+        _ if value.is_literal() => Some(value.to_string()),
+        _ => value.constant().map(|folded| folded.to_string()), // "2 + 3" is written as 5
+    }
+}
+
+fn value_token(text: &str, started_at: &Token) -> Token {
+    Token::derived(text.trim(), started_at)
+}
+
+/// The size of an "ibegyou" block. A fixed one has to be at least 1 here, any other is checked while the program runs.
+fn heap_size(size_token: &Token, name: &str, symbols: &SymbolTable) -> Result<String, MommyLangError> {
+    let size_val = resolve_constant(size_token.as_str(), symbols);
+    let size = expression::parse_operand(&[Token::derived(&size_val, size_token)], symbols)?;
+    match size.constant() {
+        Some(fixed) if fixed < 1.0 => Err(MommyLangError::InvalidArraySize),
+        Some(_) => Ok(size.to_string()),
+        None => Ok(format!("{}size({}, \"{}\", {})", constants::C_GUARD_PREFIX, size, name, symbols.line())),
+    }
+}
+
+fn heap_size_name(name: &str) -> String {
+    format!("{}{}", constants::C_SIZE_PREFIX, name)
 }
 
 pub fn c_null(value: &str) -> String{
@@ -316,4 +668,126 @@ pub fn c_null(value: &str) -> String{
         return constants::C_NULL.to_string();
     }
     value.to_string()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{declared, run};
+
+    #[test]
+    fn array_takes_a_list_or_a_string() {
+        let mut symbols = SymbolTable::new();
+        assert_eq!(run("group 5 in xs as int with 1, 2, 3", &mut symbols).unwrap(), "int xs[5] = {1, 2, 3};");
+        assert_eq!(run("group 6 in word as ascii with \"HELLO\"", &mut symbols).unwrap(), "int word[6] = {72, 69, 76, 76, 79};");
+        assert_eq!(run("group 2 in ys as float with 1.5, 2", &mut symbols).unwrap(), "float ys[2] = {1.5, 2};");
+    }
+
+    #[test]
+    fn initializer_has_to_fit() {
+        let mut symbols = SymbolTable::new();
+        assert_eq!(run("group 2 in xs as int with 1, 2, 3", &mut symbols), Err(MommyLangError::InitializerTooLong));
+        assert_eq!(run("group 3 in xs as ascii with \"HELLO\"", &mut symbols), Err(MommyLangError::InitializerTooLong));
+        assert_eq!(run("group 3 in xs as int with 1, , 2", &mut symbols), Err(MommyLangError::SyntaxError));
+        assert_eq!(run("group 3 in xs as int with", &mut symbols), Err(MommyLangError::MissingArguments));
+        assert!(symbols.get("xs").is_none());
+    }
+
+    #[test]
+    fn variable_index_is_checked_when_asked() {
+        let mut symbols = SymbolTable::new();
+        run("group 3 in xs as int", &mut symbols).unwrap();
+        run("mayihave 1 in i as int", &mut symbols).unwrap();
+        assert_eq!(run("replace xs in i with 1", &mut symbols).unwrap(), "xs[i] = 1;");
+
+        symbols.set_bounds_check(true);
+        assert_eq!(run("replace xs in i with 1", &mut symbols).unwrap(), "xs[_mommy_guard_index(i, 3, 0)] = 1;");
+        assert_eq!(run("replace xs in 2 with 1", &mut symbols).unwrap(), "xs[2] = 1;");
+        assert_eq!(run("replace xs in 3 with 1", &mut symbols), Err(MommyLangError::AccessViolation));
+    }
+
+    #[test]
+    fn heap_blocks_resize_and_keep_their_size() {
+        let mut symbols = declared(&["ibegyou 2 in p as int", "group 3 in xs as int", "mayihave 1.5 in f as float",
+            "mayihave 0 in n as int"]);
+        assert_eq!(c_length(symbols.get("p").unwrap()), Some("2"));
+        assert_eq!(c_length(symbols.get("xs").unwrap()), Some("3"));
+
+        let c_code = run("ibegyoumore 5 in p", &mut symbols).unwrap();
+        assert!(c_code.contains("int _mommy_wanted = 5;"), "{}", c_code);
+        assert!(c_code.contains("realloc(p, _mommy_wanted * sizeof(int))"), "{}", c_code);
+        assert!(c_code.contains("_mommy_size_p = _mommy_wanted;"), "{}", c_code);
+        assert!(c_code.contains("exit(1);"), "{}", c_code); // "return 1" would only leave a chore
+        assert_eq!(c_length(symbols.get("p").unwrap()), Some("_mommy_size_p"));
+        assert_eq!(run("measure p in n", &mut symbols).unwrap(), "n = _mommy_size_p;");
+        assert_eq!(run("measure xs in n", &mut symbols).unwrap(), "n = 3;");
+
+        assert_eq!(run("ibegyoumore 0 in p", &mut symbols), Err(MommyLangError::InvalidArraySize));
+        assert_eq!(run("ibegyoumore 5 in xs", &mut symbols), Err(MommyLangError::NotHeapMemory));
+        assert!(matches!(run("ibegyoumore f in p", &mut symbols), Err(MommyLangError::TypeMismatchOn(..))));
+    }
+
+    #[test]
+    fn grids_take_one_index_per_side() {
+        let mut symbols = declared(&["mayihave 0 in i as int"]);
+        assert_eq!(run("group 3,4 in board as int", &mut symbols).unwrap(), "int board[3][4] = {0};");
+        assert_eq!(run("group 2,2 in box as int with 1, 2, 3, 4", &mut symbols).unwrap(), "int box[2][2] = {1, 2, 3, 4};");
+        assert_eq!(run("replace board in 1,2 with 5", &mut symbols).unwrap(), "board[1][2] = 5;");
+        assert_eq!(run("replace i with board in 2,3", &mut symbols).unwrap(), "i = board[2][3];");
+        assert_eq!(run("replace board in 3,0 with 5", &mut symbols), Err(MommyLangError::AccessViolation));
+        assert_eq!(run("replace board in 1 with 5", &mut symbols), Err(MommyLangError::WrongIndexCount));
+
+        symbols.set_bounds_check(true);
+        assert_eq!(run("replace board in i,2 with 5", &mut symbols).unwrap(), "board[_mommy_guard_index(i, 3, 0)][2] = 5;");
+    }
+
+    #[test]
+    fn array_write_takes_a_whole_expression() {
+        let mut symbols = declared(&["group 3 in xs as int", "mayihave 1 in a as int"]);
+
+        assert_eq!(run("replace xs in 0 with a + 10", &mut symbols).unwrap(), "xs[0] = a + 10;");
+        assert_eq!(run("replace xs in 1 with (a + 1) * 2", &mut symbols).unwrap(), "xs[1] = (a + 1) * 2;");
+        assert_eq!(run("replace xs in 0 with a 10", &mut symbols), Err(MommyLangError::SyntaxError));
+    }
+
+    #[test]
+    fn string_initializer_decodes_escapes() {
+        let mut symbols = SymbolTable::new();
+        assert_eq!(run("group 4 in s as ascii with \"a\\\"b\\n\"", &mut symbols).unwrap(), "int s[4] = {97, 34, 98, 10};");
+        assert_eq!(run("group 3 in t as ascii with \"a\\\"b\\n\"", &mut symbols), Err(MommyLangError::InitializerTooLong));
+    }
+
+    #[test]
+    fn array_read_takes_nothing_after_the_index() {
+        let mut symbols = declared(&["group 3 in xs as int", "mayihave 0 in b as int"]);
+
+        assert_eq!(run("replace b with xs in 0", &mut symbols).unwrap(), "b = xs[0];");
+        assert_eq!(run("replace b with xs in 0 + 5", &mut symbols), Err(MommyLangError::SyntaxError));
+    }
+
+    #[test]
+    fn constant_takes_folded_math_and_leaves_nothing_when_not_fixed() {
+        let mut symbols = SymbolTable::new();
+        assert_eq!(run("always 2 + 3 in n as int", &mut symbols).unwrap(), "const int n = 5;");
+        assert_eq!(symbols.get("n").and_then(|s| s.constant.clone()), Some("5".to_string()));
+
+        run("mayihave 1 in a as int", &mut symbols).unwrap();
+        assert_eq!(run("always a + 1 in m as int", &mut symbols), Err(MommyLangError::ConstantNotFixed));
+        assert!(symbols.get("m").is_none());
+    }
+
+    #[test]
+    fn heap_size_has_to_be_at_least_one() {
+        let mut symbols = SymbolTable::new();
+        assert_eq!(run("ibegyou 0 in xs as int", &mut symbols), Err(MommyLangError::InvalidArraySize));
+        assert_eq!(run("ibegyou -3 in xs as int", &mut symbols), Err(MommyLangError::InvalidArraySize));
+        assert!(symbols.get("xs").is_none());
+
+        run("ibegyou 3 in xs as int", &mut symbols).unwrap();
+        assert_eq!(run("ibegyoumore -1 in xs", &mut symbols), Err(MommyLangError::InvalidArraySize));
+
+        run("mayihave 0 in n as int", &mut symbols).unwrap();
+        assert!(run("ibegyou n in ys as int", &mut symbols).unwrap().starts_with("int _mommy_size_ys = _mommy_guard_size(n, \"ys\", "));
+        assert!(run("ibegyoumore n in xs", &mut symbols).unwrap().contains("_mommy_guard_size(n, \"xs\", "));
+    }
+}
@@ -0,0 +1,260 @@
+//!
+//!
+//! This is the diagnostic crate of mommylang.
+//!
+//! Every complaint becomes a Diagnostic: how bad it is, its code, mommy's words, where it happened and a hint.
+//! Rendered, it points at the spot:
+//!
+//! ```text
+//! error[E003]: Who is that? You are making up names again. Define them first or stay silent.
+//!   --> sandbox/hello.mommy:4:5
+//!   |
+//! 4 | say ghost
+//!   |     ^^^^^
+//!   = help: declare it first: mayihave <VALUE> in <NAME> as <TYPE>
+//! ```
+//!
+//! Note:
+//! 1. The lexer knows exactly where a bad quote or escape is. Everything else is checked a statement at a time,
+//!    so the spot is worked out afterwards from the statement's tokens (see `blame`).
+//! 2. An unknown name points at the name, a block word in the wrong place points at the word, a statement
+//!    that stops too early points just past its end. Anything else underlines the whole statement.
+//! 3. A block that is never closed points at the line that opened it. A complaint about the whole file
+//!    (it cannot be read, too many mistakes) has no span, so it only names the file.
+//!    Every diagnostic of a run is about the same file, so it is given when rendering.
+//! 4. The message is the one from responses.rs, word for word. The code and the help note come from there too.
+//!
+//!
+use crate::constants;
+use crate::responses::{MommyLangError, MommyLangWarning};
+use crate::symbol_table::SymbolTable;
+use crate::syntax_lexer::{self, Span, Token, TokenKind};
+use crate::validate_syntax;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Severity {
+    Error,   // Nothing gets compiled
+    Warning, // It compiles, but mommy has notes
+}
+
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub span: Option<Span>,
+    pub help: Option<&'static str>,
+    excerpt: Option<String>, // The source line the span points into
+}
+
+impl Diagnostic {
+    pub fn error(error: &MommyLangError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code: error.code(),
+            message: error.to_string(),
+            span: None,
+            help: error.help(),
+            excerpt: None,
+        }
+    }
+
+    pub fn warning(warning: &MommyLangWarning) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            code: warning.code(),
+            message: warning.to_string(),
+            span: None,
+            help: warning.help(),
+            excerpt: None,
+        }
+    }
+
+    pub fn at(mut self, span: Option<Span>) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Keeps the line of `source` the span points into, so the renderer can show it.
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.excerpt = self.span
+            .and_then(|span| source.lines().nth(span.line.checked_sub(1)?))
+            .map(|line| line.trim_end().to_string());
+        self
+    }
+
+    /// One entry per printed line: the complaint, where in `file` it is, the source line with a caret under it, the hint.
+    pub fn render(&self, file: &str) -> Vec<String> {
+        let severity = match self.severity {
+            Severity::Error => constants::DIAG_ERROR,
+            Severity::Warning => constants::DIAG_WARNING,
+        };
+        let mut lines = vec![format!("{}[{}]: {}", severity, self.code, self.message)];
+
+        let number = self.span.map(|span| span.line.to_string()).unwrap_or_default();
+        let pad = " ".repeat(number.len());
+
+        match self.span {
+            Some(span) => lines.push(format!("{} {}{}:{}:{}", pad, constants::DIAG_ARROW, file, span.line, span.column)),
+            None => lines.push(format!("{} {}{}", pad, constants::DIAG_ARROW, file)),
+        }
+
+        if let (Some(excerpt), Some(span)) = (&self.excerpt, self.span) {
+            lines.push(format!("{} {}", pad, constants::DIAG_GUTTER));
+            lines.push(format!("{} {} {}", number, constants::DIAG_GUTTER, excerpt));
+            lines.push(format!("{} {} {}", pad, constants::DIAG_GUTTER, caret(excerpt, span)));
+        }
+
+        if let Some(help) = self.help {
+            lines.push(format!("{} {}{}", pad, constants::DIAG_HELP, help));
+        }
+        lines
+    }
+}
+
+// ================================================================
+// PUBLIC FUNCTIONS (The Logic)
+// ================================================================
+
+/// Where on its line a statement's error most likely comes from. None for an empty statement.
+pub fn blame(error: &MommyLangError, tokens: &[Token], symbols: &SymbolTable) -> Option<Span> {
+    let first = tokens.first()?;
+    let last = tokens.last()?;
+    let statement = cover(tokens.get(1).unwrap_or(first), last);
+
+    Some(match error {
+        // Where the rest of the sentence should have been
+        MommyLangError::MissingArguments => Span { column: last.span.column + last.span.length + 1, length: 1, ..last.span },
+        MommyLangError::TypeMismatchOn(target, value) => quoted_run(tokens, value)
+            .or_else(|| quoted_run(tokens, target))
+            .unwrap_or(statement),
+        MommyLangError::UndeclaredVariable | MommyLangError::OutOfScopeVariable | MommyLangError::UnknownField |
+        MommyLangError::UndeclaredFunction | MommyLangError::UnknownLoop | MommyLangError::UnknownPackage =>
+            unknown_name(tokens, symbols).unwrap_or(statement),
+        // The block word itself is what is out of place
+        MommyLangError::UnexpectedDone | MommyLangError::UnexpectedSatisfied | MommyLangError::UnexpectedSkip |
+        MommyLangError::UnexpectedAgain | MommyLangError::MissingAgain | MommyLangError::OrphanElse |
+        MommyLangError::ElseAfterElse | MommyLangError::ArmOutsidePick | MommyLangError::ArmAfterOtherwise |
+        MommyLangError::OutsideArm | MommyLangError::NestedFunction | MommyLangError::NestedType |
+        MommyLangError::UnexpectedGiveBack | MommyLangError::LeaveInsideFunction => first.span,
+        _ => statement,
+    })
+}
+
+/// The token spelled `name`, or the whole statement when it is not there. Used for warnings about a variable.
+pub fn name_span(tokens: &[Token], name: &str) -> Option<Span> {
+    match tokens.iter().find(|token| token.text == name) {
+        Some(token) => Some(token.span),
+        None => Some(cover(tokens.first()?, tokens.last()?)),
+    }
+}
+
+// ================================================================
+// HELPERS
+// ================================================================
+
+/// From the start of one token to the end of another.
+fn cover(from: &Token, to: &Token) -> Span {
+    Span { length: to.span.column + to.span.length - from.span.column, ..from.span }
+}
+
+/// The caret line: spaces up to the column (tabs kept, so it lines up), then one "^" per character.
+fn caret(excerpt: &str, span: Span) -> String {
+    let lead: String = excerpt.chars()
+        .take(span.column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let missing = span.column.saturating_sub(1).saturating_sub(lead.chars().count()); // Past the end of the line
+    format!("{}{}{}", lead, " ".repeat(missing), constants::SYM_CARET.to_string().repeat(span.length.max(1)))
+}
+
+/// "'age' (int)" from a type mismatch, found back among the tokens it was joined from.
+fn quoted_run(tokens: &[Token], described: &str) -> Option<Span> {
+    let text = described.strip_prefix('\'')?.rsplit_once("' (")?.0;
+    let count = text.split_whitespace().count();
+    (0..tokens.len()).filter_map(|start| tokens.get(start..start + count))
+        .find(|run| syntax_lexer::join(run) == text)
+        .map(|run| cover(&run[0], &run[count - 1]))
+}
+
+/// The first name on the line that nobody declared. A name the statement is about to declare
+/// (after "in", "as" or "named", or before "from") only counts when there is nothing else to blame.
+fn unknown_name(tokens: &[Token], symbols: &SymbolTable) -> Option<Span> {
+    let mut declared = None;
+
+    for (index, token) in tokens.iter().enumerate().skip(1) {
+        if matches!(token.kind, TokenKind::Text | TokenKind::Char | TokenKind::Number | TokenKind::Keyword) {
+            continue;
+        }
+        let declares = [constants::KW_IN, constants::KW_AS, constants::KW_NAMED].iter().any(|word| tokens[index - 1].is_keyword(word)) ||
+            tokens.get(index + 1).is_some_and(|next| next.is_keyword(constants::KW_FROM));
+
+        for name in names_in(token) {
+            if is_known(name.as_str(), symbols) {
+                continue;
+            }
+            if !declares {
+                return Some(name.span);
+            }
+            declared.get_or_insert(name.span);
+        }
+    }
+    declared
+}
+
+/// The names inside a token, each with its own span: "fib(n-1)" has "fib" and "n".
+fn names_in(token: &Token) -> Vec<Token> {
+    syntax_lexer::split(token).unwrap_or_default()
+        .into_iter()
+        .filter(|piece| piece.kind == TokenKind::Identifier)
+        .collect()
+}
+
+fn is_known(name: &str, symbols: &SymbolTable) -> bool {
+    syntax_lexer::classify(name) == TokenKind::Keyword ||
+        validate_syntax::is_builtin_type(name) ||
+        symbols.get(name).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol_table::Symbol;
+
+    const SOURCE: &str = "mayihave 1 in x as int\nsay ghost\nsay x\nsay x\nsay x\nsay x\nsay x\nsay x\nsay x\n\treplace x with (x + 1\n";
+
+    fn located(error: MommyLangError, line: usize, symbols: &SymbolTable) -> Diagnostic {
+        let Ok(lexed) = syntax_lexer::tokenize(SOURCE.lines().nth(line - 1).unwrap(), line) else { panic!("line {} did not lex", line) };
+        Diagnostic::error(&error).at(blame(&error, &lexed.tokens, symbols)).with_source(SOURCE)
+    }
+
+    #[test]
+    fn several_errors_render_one_after_another() {
+        let mut symbols = SymbolTable::new();
+        symbols.insert("x", Symbol::scalar(constants::TYPE_INT));
+
+        let diagnostics = [
+            located(MommyLangError::UndeclaredVariable, 2, &symbols),
+            located(MommyLangError::UnbalancedParentheses, 10, &symbols),
+            Diagnostic::error(&MommyLangError::UnclosedBlock),
+        ];
+        let rendered: Vec<String> = diagnostics.iter().flat_map(|diagnostic| diagnostic.render("main.mommy")).collect();
+
+        let expected = [
+            format!("error[E003]: {}", MommyLangError::UndeclaredVariable),
+            "  --> main.mommy:2:5".to_string(),
+            "  |".to_string(),
+            "2 | say ghost".to_string(),
+            "  |     ^^^^^".to_string(),
+            format!("  = help: {}", MommyLangError::UndeclaredVariable.help().unwrap()),
+            format!("error[E204]: {}", MommyLangError::UnbalancedParentheses),
+            "   --> main.mommy:10:10".to_string(), // Two digits of line number, so the gutter moves over
+            "   |".to_string(),
+            "10 | \treplace x with (x + 1".to_string(),
+            "   | \t        ^^^^^^^^^^^^^".to_string(),
+            format!("error[E010]: {}", MommyLangError::UnclosedBlock),
+            " --> main.mommy".to_string(),
+            format!(" = help: {}", MommyLangError::UnclosedBlock.help().unwrap()),
+        ];
+        assert_eq!(rendered, expected);
+    }
+}
@@ -0,0 +1,656 @@
+//!
+//!
+//! This is the expression crate of mommylang.
+//!
+//! Language Syntax: "(a + b) * 2", "-x % 3", "total / count"
+//! Condition Syntax: "grade >= 90 and not (absent or late)"
+//!
+//! Note:
+//! 1. Operators follow the usual math precedence: "* / %" before "+ -",
+//!    parentheses go first, and "-" can be used in front of a value.
+//! 2. Conditions go lowest: comparisons, then "not", then "and", then "or".
+//!    "&&", "||" and "!" are accepted too, for people who cannot let go of C.
+//! 3. Operands can be numbers, 'c' character literals, "text" literals, null, yes/no (true/false), declared variables or chore calls
+//!    like "fib(n - 1)". Every variable and chore is checked against the symbol table before we emit C.
+//!    Record fields are variables too, "ann.grade".
+//! 4. Two texts (or Strings) can be compared with any comparison, "name == "mommy"" or "a < b".
+//!    That becomes a call to the text runtime, C would only compare the pointers.
+//! 5. The lexer splits on whitespace, so "(a+b)" and "( a + b )" are the same thing.
+//!    Glued pieces are taken apart by the lexer's `split`, the parser only looks at each piece's kind.
+//!
+//!
+//!
+use std::fmt;
+use crate::constants;
+use crate::functions;
+use crate::lang_enums::SymbolKind;
+use crate::responses::MommyLangError;
+use crate::symbol_table::{Symbol, SymbolTable};
+use crate::syntax_lexer::{self, Token, TokenKind};
+use crate::type_checker;
+
+pub enum Expr {
+    Number(String),
+    Char(String),
+    Text(String),
+    Null,
+    Bool(bool),
+    Variable(String),
+    Call(String, Vec<Expr>),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Group(Box<Expr>),
+    Binary(Box<Expr>, &'static str, Box<Expr>),
+    TextCompare(Box<Expr>, &'static str, Box<Expr>), // a comparison between two texts
+    Guarded(Box<Expr>, String, usize, bool), // a divisor checked for zero at runtime: the value, as written, its line and if it is a float
+}
+
+/// What an expression evaluates to, as far as the checker cares.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ValueKind {
+    Number,  // int, float, char, ascii
+    Text,    // String / char*
+    Pointer, // box and ibegyou blocks
+    Array,   // a whole group, not a single slot
+    Record,  // a whole record, only copied, never calculated with
+    Choice,  // a member of a "choices", only compared with its own kind
+    Logic,   // bool, or the result of a comparison, "and", "or", "not"
+    Null,
+    Void,    // a chore that gives nothing back
+}
+
+enum ExprToken {
+    Number(String),
+    Char(String),
+    Text(String),
+    Null,
+    Bool(bool),
+    Ident(String),
+    Op(&'static str),
+    Open,
+    Close,
+    Comma,
+}
+
+// ================================================================
+// PUBLIC FUNCTIONS (The Logic)
+// ================================================================
+
+/// Parses and validates a value for an assignment or declaration. A condition is a bool value here.
+pub fn parse_value(tokens: &[Token], symbols: &SymbolTable) -> Result<Expr, MommyLangError> {
+    to_c(read_value(tokens, symbols)?, symbols)
+}
+
+/// Same as `parse_value`, but the result is going straight into math, so it has to be a number.
+pub fn parse_operand(tokens: &[Token], symbols: &SymbolTable) -> Result<Expr, MommyLangError> {
+    to_c(read_operand(tokens, symbols)?, symbols)
+}
+
+/// `parse_value` without the C rewrites, the value as it was written. For checks that name what they found.
+pub fn read_value(tokens: &[Token], symbols: &SymbolTable) -> Result<Expr, MommyLangError> {
+    let expr = parse_tokens(tokens)?;
+    match kind_of(&expr, symbols)? {
+        ValueKind::Array | ValueKind::Void => Err(MommyLangError::TypeMismatch),
+        _ => Ok(expr),
+    }
+}
+
+/// `parse_operand` without the C rewrites.
+pub fn read_operand(tokens: &[Token], symbols: &SymbolTable) -> Result<Expr, MommyLangError> {
+    let expr = parse_tokens(tokens)?;
+    ensure_number(kind_of(&expr, symbols)?)?;
+    Ok(expr)
+}
+
+/// Parses a full condition for "ask" and "punishmeif" into C.
+pub fn compile_condition(tokens: &[Token], symbols: &SymbolTable) -> Result<String, MommyLangError> {
+    let expr = parse_tokens(tokens)?;
+    ensure_truthy(kind_of(&expr, symbols)?)?;
+    Ok(to_c(expr, symbols)?.to_string())
+}
+
+/// Parses a chore call used as its own statement. Chores that give nothing back are fine here.
+pub fn parse_call(tokens: &[Token], symbols: &SymbolTable) -> Result<Expr, MommyLangError> {
+    let expr = parse_tokens(tokens)?;
+    if !matches!(expr, Expr::Call(..)) {
+        return Err(MommyLangError::SyntaxError);
+    }
+    kind_of(&expr, symbols)?;
+    to_c(expr, symbols)
+}
+
+/// Rewrites a checked expression for C: every comparison between two texts becomes a runtime call,
+/// and every divisor that is not a fixed number gets a runtime guard. Run only after `kind_of` passed.
+pub fn to_c(expr: Expr, symbols: &SymbolTable) -> Result<Expr, MommyLangError> {
+    let lower = |inner: Box<Expr>| to_c(*inner, symbols).map(Box::new);
+
+    Ok(match expr {
+        Expr::Binary(left, op, right) if is_comparison(op)
+            && kind_of(&left, symbols)? == ValueKind::Text
+            && kind_of(&right, symbols)? == ValueKind::Text => Expr::TextCompare(left, op, right),
+        Expr::Binary(left, op, right) if (op == constants::C_OP_DIV || op == constants::C_OP_MOD) && !right.is_fixed() => {
+            let written = match &*right {
+                Expr::Group(inner) => inner.to_string(),
+                other => other.to_string(),
+            };
+            let float = type_checker::is_float(&right, symbols);
+            Expr::Binary(lower(left)?, op, Box::new(Expr::Guarded(lower(right)?, written, symbols.line(), float)))
+        }
+        Expr::Binary(left, op, right) => Expr::Binary(lower(left)?, op, lower(right)?),
+        Expr::Negate(inner) => Expr::Negate(lower(inner)?),
+        Expr::Not(inner) => Expr::Not(lower(inner)?),
+        Expr::Group(inner) => Expr::Group(lower(inner)?),
+        Expr::Call(name, args) => Expr::Call(
+            name,
+            args.into_iter().map(|arg| to_c(arg, symbols)).collect::<Result<_, _>>()?,
+        ),
+        other => other,
+    })
+}
+
+impl Expr {
+    /// True when the expression has to be wrapped before being glued to another operator.
+    pub fn is_compound(&self) -> bool {
+        matches!(self, Expr::Binary(..) | Expr::TextCompare(..) | Expr::Negate(..) | Expr::Not(..))
+    }
+
+    /// "0", "0.0", "0e0", "-0", "2 - 2" and friends. Anything that works out to zero before the program even runs.
+    pub fn is_literal_zero(&self) -> bool {
+        self.constant() == Some(0.0)
+    }
+
+    /// A literal, or math on nothing but numbers. Its value is known before the program runs.
+    pub fn is_fixed(&self) -> bool {
+        self.is_literal() || self.constant().is_some()
+    }
+
+    /// What an expression made only of numbers comes out as, "2 * (3 - 3)" is 0. None as soon as a name is involved.
+    pub fn constant(&self) -> Option<f64> {
+        self.fold().map(|(value, _)| value)
+    }
+
+    pub fn is_literal(&self) -> bool {
+        match self {
+            Expr::Number(_) | Expr::Char(_) => true,
+            Expr::Negate(inner) | Expr::Group(inner) => inner.is_literal(),
+            _ => false,
+        }
+    }
+}
+
+impl Expr {
+    /// The value and whether C would treat it as a double. "7 / 2" is 3, "7.0 / 2" is 3.5.
+    fn fold(&self) -> Option<(f64, bool)> {
+        match self {
+            Expr::Number(n) => Some((n.parse().ok()?, is_float_literal(n))),
+            Expr::Negate(inner) => inner.fold().map(|(value, is_float)| (-value, is_float)),
+            Expr::Group(inner) => inner.fold(),
+            Expr::Binary(left, op, right) => {
+                let (left, left_float) = left.fold()?;
+                let (right, right_float) = right.fold()?;
+                let is_float = left_float || right_float;
+
+                let value = match *op {
+                    constants::C_OP_ADD => left + right,
+                    constants::C_OP_SUB => left - right,
+                    constants::C_OP_MUL => left * right,
+                    constants::C_OP_DIV | constants::C_OP_MOD if right == 0.0 => return None, // Nothing to fold, and reported elsewhere
+                    constants::C_OP_DIV if is_float => left / right,
+                    constants::C_OP_DIV => (left / right).trunc(),
+                    constants::C_OP_MOD => left % right,
+                    _ => return None,
+                };
+                Some((value, is_float))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Number(n) | Expr::Char(n) | Expr::Text(n) | Expr::Variable(n) => write!(f, "{}", n),
+            Expr::Null => write!(f, "{}", constants::C_NULL),
+            Expr::Bool(true) => write!(f, "{}", constants::C_VAL_ONE),
+            Expr::Bool(false) => write!(f, "{}", constants::C_VAL_ZERO),
+            Expr::Call(name, args) => {
+                let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+            Expr::Negate(inner) if matches!(**inner, Expr::Negate(_)) => write!(f, "-({})", inner), // "--x" is a decrement in C
+            Expr::Negate(inner) => write!(f, "-{}", inner),
+            // "not a > b" means !(a > b), but C would read "!a > b" as (!a) > b
+            Expr::Not(inner) if inner.is_compound() => write!(f, "!({})", inner),
+            Expr::Not(inner) => write!(f, "!{}", inner),
+            Expr::Group(inner) => write!(f, "({})", inner),
+            Expr::Binary(left, op, right) => write!(f, "{} {} {}", left, op, right),
+            Expr::TextCompare(left, op, right) =>
+                write!(f, "{}compare({}, {}) {} {}", constants::C_TEXT_PREFIX, left, right, op, constants::C_VAL_ZERO),
+            Expr::Guarded(inner, written, line, float) => write!(f, "{}divisor{}({}, \"{}\", {})",
+                constants::C_GUARD_PREFIX, if *float { "_float" } else { "" }, inner, written.replace('\\', "\\\\").replace('"', "\\\""), line),
+        }
+    }
+}
+
+// ================================================================
+// VALIDATION (Type kinds)
+// ================================================================
+
+pub fn kind_of(expr: &Expr, symbols: &SymbolTable) -> Result<ValueKind, MommyLangError> {
+    match expr {
+        Expr::Number(_) | Expr::Char(_) => Ok(ValueKind::Number),
+        Expr::Text(_) => Ok(ValueKind::Text),
+        Expr::Null => Ok(ValueKind::Null),
+        Expr::Bool(_) => Ok(ValueKind::Logic),
+        Expr::Variable(name) => {
+            let symbol = symbols.lookup(name)?;
+            if symbol.kind == SymbolKind::Function { // "replace x with fib", forgot the ()
+                return Err(MommyLangError::TypeMismatch);
+            }
+            Ok(kind_of_symbol(symbol))
+        }
+        Expr::Call(name, args) => {
+            let chore = symbols.get(name)
+                .filter(|symbol| symbol.kind == SymbolKind::Function)
+                .ok_or(MommyLangError::UndeclaredFunction)?;
+
+            if args.len() != chore.params.len() {
+                return Err(MommyLangError::WrongArgumentCount);
+            }
+            for (arg, param_type) in args.iter().zip(&chore.params) {
+                functions::ensure_fits(kind_of(arg, symbols)?, param_type)?;
+            }
+
+            if chore.data_type == constants::C_KW_VOID {
+                return Ok(ValueKind::Void);
+            }
+            Ok(kind_of_type(&chore.data_type))
+        }
+        Expr::Group(inner) | Expr::Guarded(inner, ..) => kind_of(inner, symbols),
+        Expr::Negate(inner) => {
+            ensure_number(kind_of(inner, symbols)?)?;
+            Ok(ValueKind::Number)
+        }
+        Expr::Not(inner) => {
+            ensure_truthy(kind_of(inner, symbols)?)?;
+            Ok(ValueKind::Logic)
+        }
+        Expr::TextCompare(..) => Ok(ValueKind::Logic),
+        Expr::Binary(left, op, right) => {
+            let left_kind = kind_of(left, symbols)?;
+            let right_kind = kind_of(right, symbols)?;
+
+            if is_logical(op) {
+                ensure_truthy(left_kind)?;
+                ensure_truthy(right_kind)?;
+                return Ok(ValueKind::Logic);
+            }
+
+            if is_comparison(op) {
+                let same_choice = left_kind != ValueKind::Choice || choice_of(left, symbols) == choice_of(right, symbols); // A mood is never a color
+                if ensure_comparable(left_kind, op, right_kind).is_err() || !same_choice {
+                    return Err(MommyLangError::TypeMismatchOn(
+                        type_checker::describe(left, symbols),
+                        type_checker::describe(right, symbols),
+                    ));
+                }
+                return Ok(ValueKind::Logic);
+            }
+
+            if (*op == constants::C_OP_DIV || *op == constants::C_OP_MOD) && right.is_literal_zero() { // No division on 0!
+                return Err(MommyLangError::DivideByZero);
+            }
+            ensure_number(left_kind)?;
+            ensure_number(right_kind)?;
+            Ok(ValueKind::Number)
+        }
+    }
+}
+
+pub fn kind_of_symbol(symbol: &Symbol) -> ValueKind {
+    match symbol.kind {
+        SymbolKind::Scalar if symbol.is_record() => ValueKind::Record,
+        SymbolKind::Scalar if symbol.is_choice() => ValueKind::Choice,
+        SymbolKind::Scalar => kind_of_type(&symbol.data_type),
+        SymbolKind::Pointer | SymbolKind::Heap => ValueKind::Pointer,
+        SymbolKind::Array => ValueKind::Array,
+        SymbolKind::Function | SymbolKind::Record | SymbolKind::Choice => ValueKind::Void, // A name, not a value
+    }
+}
+
+/// Same idea, for a plain type name like a chore's parameter or return type.
+pub fn kind_of_type(raw_type: &str) -> ValueKind {
+    match raw_type {
+        constants::TYPE_STRING | constants::C_TYPE_CHAR_PTR | constants::TYPE_TEXT => ValueKind::Text,
+        t if t == constants::KW_POINTER || t == constants::KW_BOX => ValueKind::Pointer,
+        constants::TYPE_BOOL => ValueKind::Logic,
+        _ => ValueKind::Number,
+    }
+}
+
+fn ensure_number(kind: ValueKind) -> Result<(), MommyLangError> {
+    match kind {
+        ValueKind::Number => Ok(()),
+        ValueKind::Text => Err(MommyLangError::MathOnString), // No math on Strings!
+        _ => Err(MommyLangError::TypeMismatch),
+    }
+}
+
+fn ensure_truthy(kind: ValueKind) -> Result<(), MommyLangError> {
+    match kind {
+        ValueKind::Number | ValueKind::Logic => Ok(()),
+        _ => Err(MommyLangError::TypeMismatch),
+    }
+}
+
+fn ensure_comparable(left: ValueKind, op: &str, right: ValueKind) -> Result<(), MommyLangError> {
+    let equality = op == constants::C_OP_EQ || op == constants::C_OP_NEQ;
+
+    match (left, right) {
+        (ValueKind::Number, ValueKind::Number) => Ok(()),
+        (ValueKind::Logic, ValueKind::Logic) if equality => Ok(()),
+        (ValueKind::Text, ValueKind::Text) => Ok(()), // alphabetical order for < and >
+        (ValueKind::Choice, ValueKind::Choice) => Ok(()), // in the order they were listed
+        // Pointers can only be checked against each other or null, "ptr < 5" is nonsense
+        (ValueKind::Pointer | ValueKind::Null, ValueKind::Pointer | ValueKind::Null) if equality => Ok(()),
+        _ => Err(MommyLangError::TypeMismatch),
+    }
+}
+
+/// Which "choices" a value belongs to, if it is one.
+fn choice_of<'a>(expr: &Expr, symbols: &'a SymbolTable) -> Option<&'a str> {
+    match expr {
+        Expr::Variable(name) => symbols.get(name).filter(|symbol| symbol.is_choice()).map(|symbol| symbol.data_type.as_str()),
+        Expr::Group(inner) => choice_of(inner, symbols),
+        _ => None,
+    }
+}
+
+// ================================================================
+// PARSER (Precedence climbing, lowest first)
+// ================================================================
+
+fn parse_tokens(tokens: &[Token]) -> Result<Expr, MommyLangError> {
+    let mut expr_tokens = Vec::new();
+    for token in tokens {
+        for piece in syntax_lexer::split(token)? {
+            expr_tokens.push(expr_token(&piece)?);
+        }
+    }
+    if expr_tokens.is_empty() {
+        return Err(MommyLangError::MissingArguments);
+    }
+
+    let mut pos = 0;
+    let expr = parse_or(&expr_tokens, &mut pos)?;
+    ensure_consumed(&expr_tokens, pos)?;
+    Ok(expr)
+}
+
+fn parse_or(tokens: &[ExprToken], pos: &mut usize) -> Result<Expr, MommyLangError> {
+    let mut left = parse_and(tokens, pos)?;
+
+    while let Some(ExprToken::Op(op)) = tokens.get(*pos) && *op == constants::C_OP_OR {
+        *pos += 1;
+        let right = parse_and(tokens, pos)?;
+        left = Expr::Binary(Box::new(left), op, Box::new(right));
+    }
+
+    Ok(left)
+}
+
+fn parse_and(tokens: &[ExprToken], pos: &mut usize) -> Result<Expr, MommyLangError> {
+    let mut left = parse_not(tokens, pos)?;
+
+    while let Some(ExprToken::Op(op)) = tokens.get(*pos) && *op == constants::C_OP_AND {
+        *pos += 1;
+        let right = parse_not(tokens, pos)?;
+        left = Expr::Binary(Box::new(left), op, Box::new(right));
+    }
+
+    Ok(left)
+}
+
+fn parse_not(tokens: &[ExprToken], pos: &mut usize) -> Result<Expr, MommyLangError> {
+    if let Some(ExprToken::Op(op)) = tokens.get(*pos) && *op == constants::C_OP_NOT {
+        *pos += 1;
+        return Ok(Expr::Not(Box::new(parse_not(tokens, pos)?)));
+    }
+    parse_comparison(tokens, pos)
+}
+
+fn parse_comparison(tokens: &[ExprToken], pos: &mut usize) -> Result<Expr, MommyLangError> {
+    let left = parse_additive(tokens, pos)?;
+
+    match tokens.get(*pos) {
+        Some(ExprToken::Op(op)) if is_comparison(op) => {
+            *pos += 1;
+            let right = parse_additive(tokens, pos)?;
+
+            // "a < b < c" is not a thing
+            if let Some(ExprToken::Op(next)) = tokens.get(*pos) && is_comparison(next) {
+                return Err(MommyLangError::MalformedOperator);
+            }
+            Ok(Expr::Binary(Box::new(left), op, Box::new(right)))
+        }
+        _ => Ok(left),
+    }
+}
+
+fn parse_additive(tokens: &[ExprToken], pos: &mut usize) -> Result<Expr, MommyLangError> {
+    let mut left = parse_term(tokens, pos)?;
+
+    while let Some(ExprToken::Op(op)) = tokens.get(*pos) {
+        if *op != constants::C_OP_ADD && *op != constants::C_OP_SUB {
+            break;
+        }
+        *pos += 1;
+        let right = parse_term(tokens, pos)?;
+        left = Expr::Binary(Box::new(left), op, Box::new(right));
+    }
+
+    Ok(left)
+}
+
+fn parse_term(tokens: &[ExprToken], pos: &mut usize) -> Result<Expr, MommyLangError> {
+    let mut left = parse_unary(tokens, pos)?;
+
+    while let Some(ExprToken::Op(op)) = tokens.get(*pos) {
+        if *op != constants::C_OP_MUL && *op != constants::C_OP_DIV && *op != constants::C_OP_MOD {
+            break;
+        }
+        *pos += 1;
+        let right = parse_unary(tokens, pos)?;
+        left = Expr::Binary(Box::new(left), op, Box::new(right));
+    }
+
+    Ok(left)
+}
+
+fn parse_unary(tokens: &[ExprToken], pos: &mut usize) -> Result<Expr, MommyLangError> {
+    if let Some(ExprToken::Op(op)) = tokens.get(*pos) && *op == constants::C_OP_SUB {
+        *pos += 1;
+        return Ok(Expr::Negate(Box::new(parse_unary(tokens, pos)?)));
+    }
+    parse_primary(tokens, pos)
+}
+
+fn parse_primary(tokens: &[ExprToken], pos: &mut usize) -> Result<Expr, MommyLangError> {
+    let token = match tokens.get(*pos) {
+        Some(token) => token,
+        None if *pos == 0 => return Err(MommyLangError::MissingArguments),
+        None => return Err(MommyLangError::MalformedOperator), // "a >" with nothing after it
+    };
+    *pos += 1;
+
+    match token {
+        ExprToken::Number(n) => Ok(Expr::Number(n.clone())),
+        ExprToken::Char(c) => Ok(Expr::Char(c.clone())),
+        ExprToken::Text(t) => Ok(Expr::Text(t.clone())),
+        ExprToken::Null => Ok(Expr::Null),
+        ExprToken::Bool(value) => Ok(Expr::Bool(*value)),
+        ExprToken::Ident(name) if matches!(tokens.get(*pos), Some(ExprToken::Open)) => {
+            *pos += 1;
+            Ok(Expr::Call(name.clone(), parse_args(tokens, pos)?))
+        }
+        ExprToken::Ident(name) => Ok(Expr::Variable(name.clone())),
+        ExprToken::Open => {
+            let inner = parse_or(tokens, pos)?;
+            match tokens.get(*pos) {
+                Some(ExprToken::Close) => {
+                    *pos += 1;
+                    Ok(Expr::Group(Box::new(inner)))
+                }
+                _ => Err(MommyLangError::UnbalancedParentheses),
+            }
+        }
+        ExprToken::Close => Err(MommyLangError::UnbalancedParentheses),
+        ExprToken::Comma => Err(MommyLangError::SyntaxError),
+        ExprToken::Op(_) => Err(MommyLangError::MalformedOperator), // "a > > b", "a <> b"
+    }
+}
+
+/// Arguments of a chore call, the "(" is already eaten.
+fn parse_args(tokens: &[ExprToken], pos: &mut usize) -> Result<Vec<Expr>, MommyLangError> {
+    let mut args = Vec::new();
+
+    if let Some(ExprToken::Close) = tokens.get(*pos) {
+        *pos += 1;
+        return Ok(args);
+    }
+
+    loop {
+        args.push(parse_or(tokens, pos)?);
+        match tokens.get(*pos) {
+            Some(ExprToken::Comma) => *pos += 1,
+            Some(ExprToken::Close) => {
+                *pos += 1;
+                return Ok(args);
+            }
+            _ => return Err(MommyLangError::UnbalancedParentheses),
+        }
+    }
+}
+
+// ================================================================
+// TOKENS
+// ================================================================
+
+/// What the parser makes of one piece from the lexer.
+fn expr_token(piece: &Token) -> Result<ExprToken, MommyLangError> {
+    Ok(match piece.kind {
+        TokenKind::Number => ExprToken::Number(piece.text.clone()),
+        TokenKind::Text => ExprToken::Text(piece.text.clone()),
+        TokenKind::Char => ExprToken::Char(char_literal(&piece.text)?),
+        TokenKind::Identifier => ExprToken::Ident(piece.text.clone()),
+        TokenKind::Keyword => match piece.as_str() {
+            constants::KW_AND => ExprToken::Op(constants::C_OP_AND),
+            constants::KW_OR => ExprToken::Op(constants::C_OP_OR),
+            constants::KW_NOT => ExprToken::Op(constants::C_OP_NOT),
+            constants::KW_NULL => ExprToken::Null,
+            constants::KW_YES | constants::KW_TRUE => ExprToken::Bool(true),
+            constants::KW_NO | constants::KW_FALSE => ExprToken::Bool(false),
+            _ => ExprToken::Ident(piece.text.clone()),
+        },
+        TokenKind::Punctuation => match piece.as_str() {
+            "(" => ExprToken::Open,
+            ")" => ExprToken::Close,
+            _ => ExprToken::Comma,
+        },
+        TokenKind::Operator => ExprToken::Op(match piece.as_str() {
+            "==" => constants::C_OP_EQ,
+            "!=" => constants::C_OP_NEQ,
+            "<=" => constants::C_OP_LTE,
+            ">=" => constants::C_OP_GTE,
+            "&&" => constants::C_OP_AND,
+            "||" => constants::C_OP_OR,
+            "!" => constants::C_OP_NOT,
+            "<" => constants::C_OP_LT,
+            ">" => constants::C_OP_GT,
+            "+" => constants::C_OP_ADD,
+            "-" => constants::C_OP_SUB,
+            "*" => constants::C_OP_MUL,
+            "/" => constants::C_OP_DIV,
+            "%" => constants::C_OP_MOD,
+            _ => return Err(MommyLangError::MalformedOperator),
+        }),
+        TokenKind::Compound => return Err(MommyLangError::SyntaxError), // `split` never leaves one
+    })
+}
+
+/// 'a' or an escape like '\n', one character between the quotes.
+fn char_literal(quoted: &str) -> Result<String, MommyLangError> {
+    let inner: Vec<char> = quoted[1..quoted.len() - 1].chars().collect();
+    match inner.as_slice() {
+        [c] if *c != constants::SYM_BACKSLASH => Ok(quoted.to_string()),
+        [constants::SYM_BACKSLASH, _] => Ok(quoted.to_string()),
+        _ => Err(MommyLangError::SyntaxError),
+    }
+}
+
+fn ensure_consumed(tokens: &[ExprToken], pos: usize) -> Result<(), MommyLangError> {
+    match tokens.get(pos) {
+        None => Ok(()),
+        Some(ExprToken::Close) => Err(MommyLangError::UnbalancedParentheses),
+        Some(ExprToken::Op(_)) => Err(MommyLangError::MalformedOperator),
+        Some(_) => Err(MommyLangError::SyntaxError),
+    }
+}
+
+/// "2.5" and "1e3" are doubles in C, "25" is an int.
+pub fn is_float_literal(number: &str) -> bool {
+    !number.chars().all(|c| c.is_ascii_digit())
+}
+
+pub fn is_comparison(op: &str) -> bool {
+    matches!(op, constants::C_OP_EQ | constants::C_OP_NEQ | constants::C_OP_LT |
+                 constants::C_OP_GT | constants::C_OP_LTE | constants::C_OP_GTE)
+}
+
+pub fn is_logical(op: &str) -> bool {
+    op == constants::C_OP_AND || op == constants::C_OP_OR
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::tokens;
+
+    fn read(line: &str) -> Result<Expr, MommyLangError> {
+        read_value(&tokens(line), &SymbolTable::new())
+    }
+
+    #[test]
+    fn times_goes_before_plus() {
+        assert_eq!(read("2 + 3 * 4").unwrap().constant(), Some(14.0));
+        assert_eq!(read("2*3+4").unwrap().constant(), Some(10.0));
+        assert_eq!(read("(2 + 3) * 4").unwrap().constant(), Some(20.0));
+        assert_eq!(read("-2 * 3 % 4").unwrap().constant(), Some(-2.0));
+    }
+
+    #[test]
+    fn same_level_goes_left_to_right() {
+        assert_eq!(read("10 - 4 - 3").unwrap().constant(), Some(3.0));
+        assert_eq!(read("100 / 10 / 5").unwrap().constant(), Some(2.0));
+        assert_eq!(read("7 - 2 + 1").unwrap().constant(), Some(6.0));
+    }
+
+    #[test]
+    fn and_goes_before_or() {
+        let Expr::Binary(_, op, right) = read("yes or no and no").unwrap() else { panic!("not a binary") };
+        assert_eq!(op, constants::C_OP_OR);
+        assert!(matches!(*right, Expr::Binary(_, constants::C_OP_AND, _)));
+
+        let Expr::Binary(left, op, _) = read("1 < 2 and 3 > 4").unwrap() else { panic!("not a binary") };
+        assert_eq!(op, constants::C_OP_AND);
+        assert!(matches!(*left, Expr::Binary(_, constants::C_OP_LT, _)));
+    }
+
+    #[test]
+    fn unbalanced_parentheses_are_rejected() {
+        for line in ["(1 + 2", "1 + 2)", "((1 + 2)", "(1 + 2))", ")1("] {
+            assert_eq!(read(line).err(), Some(MommyLangError::UnbalancedParentheses), "{}", line);
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//!
+//!
+//! This is the function (chore) crate of mommylang.
+//!
+//! Language Syntax:
+//! - Definition: "chore name needs a as int, b as float gives int" ... "done"
+//! - No parameters / nothing given back: "chore name" ... "done"
+//! - Return: "giveback value/expression" or just "giveback" in a chore that gives nothing
+//! - Call as a statement: "dothis name with arg1, arg2"
+//! - Call inside an expression: "replace total with name(arg1, arg2) + 1"
+//!
+//! Note:
+//! 1. Chores are emitted as separate C functions above main, with prototypes, so a chore can call itself.
+//! 2. A chore only sees its own parameters and variables, and the chores declared before it.
+//!    Variables from outside are not visible, just like in C.
+//! 3. Chores cannot be declared inside other chores, loops or conditions.
+//! 4. Parameters and return types: int, float, char, ascii, bool, String and box. Arrays and records cannot be passed yet.
+//!
+//! Symbol: kind Function, data_type is the return type, params are the parameter types.
+//!
+//!
+use crate::constants;
+use crate::declaration;
+use crate::expression::{self, Expr, ValueKind};
+use crate::responses::MommyLangError;
+use crate::symbol_table::{Symbol, SymbolTable};
+use crate::text;
+use crate::validate_syntax;
+use crate::syntax_lexer::{self, Token};
+
+const CHORE_ROOM: usize = 1; // A chore's own symbol table starts at its body
+
+/// The chore we are currently writing. Main's symbols wait here until "done".
+pub struct FunctionFrame {
+    pub name: String,
+    pub return_type: String,
+    pub header: String,
+    pub body: Vec<String>,
+    outer_symbols: SymbolTable,
+}
+
+// ================================================================
+// PUBLIC FUNCTIONS (The Logic)
+// ================================================================
+
+pub fn start_function(
+    tokens: &[Token],
+    symbols: &mut SymbolTable
+) -> Result<FunctionFrame, MommyLangError> {
+    // Syntax: chore <NAME> [needs <P> as <TYPE>, ...] [gives <TYPE>]
+
+    if tokens.len() < constants::ARGS_MIN_LEN {
+        return Err(MommyLangError::MissingArguments);
+    }
+
+    let name = tokens[constants::IDX_FUNC_NAME].as_str();
+    validate_syntax::ensure_valid_name(name)?;
+    validate_syntax::ensure_var_new(name, symbols)?;
+
+    let gives_index = tokens.iter().position(|t| t.is_keyword(constants::KW_GIVES));
+    let params_end = gives_index.unwrap_or(tokens.len());
+
+    let return_type = match gives_index {
+        Some(idx) if idx + 2 == tokens.len() => normalize_type(tokens[idx + 1].as_str())?,
+        Some(_) => return Err(MommyLangError::SyntaxError),
+        None => constants::C_KW_VOID.to_string(),
+    };
+
+    let params = match tokens.get(constants::IDX_FUNC_KEY_NEEDS) {
+        Some(t) if t.is_keyword(constants::KW_NEEDS) => parse_params(&tokens[constants::IDX_FUNC_KEY_NEEDS + 1..params_end])?,
+        Some(_) if gives_index != Some(constants::IDX_FUNC_KEY_NEEDS) => return Err(MommyLangError::SyntaxError),
+        _ => Vec::new(),
+    };
+
+    let param_types: Vec<String> = params.iter().map(|(_, t)| t.clone()).collect();
+    symbols.insert(name, Symbol::function(&return_type, param_types)); // Registered before the body, so recursion works
+
+    // The chore starts with a clean room: other chores and its own parameters, nothing else.
+    let mut local_symbols = symbols.for_chore();
+
+    let mut c_params = Vec::new();
+    for (param_name, param_type) in &params {
+        validate_syntax::ensure_valid_name(param_name)?;
+        validate_syntax::ensure_var_new(param_name, &local_symbols)?;
+        declaration::insert_symbol(param_type, param_name, &mut local_symbols);
+        c_params.push(format!("{} {}", declaration::get_c_type(param_type), param_name));
+    }
+
+    let c_params = if c_params.is_empty() { constants::C_KW_VOID.to_string() } else { c_params.join(", ") };
+    let header = format!("{} {}({})", declaration::get_c_type(&return_type), name, c_params);
+
+    Ok(FunctionFrame {
+        name: name.to_string(),
+        return_type,
+        header,
+        body: Vec::new(),
+        outer_symbols: std::mem::replace(symbols, local_symbols),
+    })
+}
+
+/// A chore whose first line failed, opened anyway so its body is still checked in a room of its own.
+/// Every parameter that can still be read is declared, and "giveback" goes by the return type as written.
+pub fn start_anyway(tokens: &[Token], symbols: &mut SymbolTable) -> FunctionFrame {
+    let gives_index = tokens.iter().position(|t| t.is_keyword(constants::KW_GIVES));
+    let return_type = gives_index.and_then(|idx| tokens.get(idx + 1))
+        .map_or(constants::C_KW_VOID.to_string(), |t| normalize_type(t.as_str()).unwrap_or_else(|_| t.to_string()));
+    let params = match tokens.get(constants::IDX_FUNC_KEY_NEEDS) {
+        Some(t) if t.is_keyword(constants::KW_NEEDS) => tokens.get(constants::IDX_FUNC_KEY_NEEDS + 1..gives_index.unwrap_or(tokens.len()))
+            .and_then(|params| parse_params(params).ok())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    let mut local_symbols = symbols.for_chore();
+    for (param_name, param_type) in &params {
+        if validate_syntax::ensure_valid_name(param_name).is_ok() && local_symbols.get(param_name).is_none() {
+            declaration::insert_symbol(param_type, param_name, &mut local_symbols);
+        }
+    }
+
+    FunctionFrame {
+        name: tokens.get(constants::IDX_FUNC_NAME).map(Token::to_string).unwrap_or_default(),
+        return_type,
+        header: String::new(),
+        body: Vec::new(),
+        outer_symbols: std::mem::replace(symbols, local_symbols),
+    }
+}
+
+/// Closes the chore and gives main its symbols back. Returns the full C definition.
+/// The texts of the chore die with it, when it runs off its end without a "giveback".
+pub fn end_function(mut frame: FunctionFrame, symbols: &mut SymbolTable) -> String {
+    let released = text::release(&symbols.texts_from(CHORE_ROOM));
+    if !released.is_empty() {
+        frame.body.push(released.trim_end().to_string());
+    }
+    let mut chore_symbols = std::mem::replace(symbols, frame.outer_symbols);
+    chore_symbols.check_leaks(); // Whatever the chore begged for dies with it
+    symbols.absorb_history(chore_symbols);
+
+    let mut definition = format!("{} {{\n", frame.header);
+    for line in &frame.body {
+        definition.push_str(&format!("    {}\n", line));
+    }
+    definition.push_str(constants::C_MAIN_END);
+    definition
+}
+
+pub fn prototype(frame: &FunctionFrame) -> String {
+    format!("{};", frame.header)
+}
+
+pub fn give_back(
+    tokens: &[Token],
+    symbols: &SymbolTable,
+    frame: &FunctionFrame
+) -> Result<String, MommyLangError> {
+    // Syntax: giveback [<VALUE/EXPRESSION>]
+
+    if frame.return_type == constants::C_KW_VOID {
+        if tokens.len() > 1 { // This chore promised nothing
+            return Err(MommyLangError::TypeMismatch);
+        }
+        return Ok(format!("{}{};", text::release(&symbols.texts_from(CHORE_ROOM)), constants::C_KW_RETURN));
+    }
+
+    if tokens.len() < constants::ARGS_MIN_LEN {
+        return Err(MommyLangError::MissingArguments);
+    }
+
+    let value = expression::parse_value(&tokens[1..], symbols)?;
+    ensure_fits(expression::kind_of(&value, symbols)?, &frame.return_type)?;
+
+    // A text that is given back belongs to the caller now, every other one is freed on the way out
+    let given_away = match &value {
+        Expr::Variable(name) => Some(name.as_str()),
+        _ => None,
+    };
+    let texts: Vec<String> = symbols.texts_from(CHORE_ROOM).into_iter()
+        .filter(|name| Some(name.as_str()) != given_away)
+        .collect();
+    if texts.is_empty() {
+        return Ok(format!("{} {};", constants::C_KW_RETURN, value));
+    }
+
+    // The value might still read one of them, so it is worked out before anything is freed
+    Ok(format!(
+        "{{ {} {} = {}; {}{} {}; }}",
+        declaration::get_c_type(&frame.return_type),
+        constants::C_RESULT_NAME,
+        value,
+        text::release(&texts),
+        constants::C_KW_RETURN,
+        constants::C_RESULT_NAME
+    ))
+}
+
+pub fn call(
+    tokens: &[Token],
+    symbols: &SymbolTable
+) -> Result<String, MommyLangError> {
+    // Syntax: dothis <NAME> [with <ARG>, <ARG>]
+
+    if tokens.len() < constants::ARGS_MIN_LEN {
+        return Err(MommyLangError::MissingArguments);
+    }
+
+    let args = match tokens.get(constants::IDX_CALL_KEY_WITH) {
+        None => String::new(),
+        Some(t) if t.is_keyword(constants::KW_WITH) && tokens.len() > constants::IDX_CALL_KEY_WITH + 1 =>
+            syntax_lexer::join(&tokens[constants::IDX_CALL_KEY_WITH + 1..]),
+        Some(t) if t.is_keyword(constants::KW_WITH) => return Err(MommyLangError::MissingArguments),
+        Some(_) => return Err(MommyLangError::SyntaxError),
+    };
+
+    let call_tokens = vec![Token::derived(&format!("{}({})", tokens[constants::IDX_FUNC_NAME], args), &tokens[constants::IDX_FUNC_NAME])];
+    let call = expression::parse_call(&call_tokens, symbols)?;
+
+    Ok(format!("{};", call))
+}
+
+// ================================================================
+// SIGNATURE HELPERS
+// ================================================================
+
+/// Can a value of this kind be passed as (or given back as) this declared type?
+pub fn ensure_fits(kind: ValueKind, declared_type: &str) -> Result<(), MommyLangError> {
+    match (expression::kind_of_type(declared_type), kind) {
+        (ValueKind::Number, ValueKind::Number) |
+        (ValueKind::Text, ValueKind::Text) |
+        (ValueKind::Logic, ValueKind::Logic) |
+        (ValueKind::Pointer, ValueKind::Pointer | ValueKind::Null) => Ok(()),
+        _ => Err(MommyLangError::TypeMismatch),
+    }
+}
+
+pub fn parse_params(tokens: &[Token]) -> Result<Vec<(String, String)>, MommyLangError> {
+    let joined = syntax_lexer::join(tokens);
+    if joined.trim().is_empty() {
+        return Err(MommyLangError::MissingArguments);
+    }
+
+    let mut params = Vec::new();
+    for param in joined.split(constants::SYM_COMMA) {
+        // Each one is: <NAME> as <TYPE>
+        let parts: Vec<&str> = param.split_whitespace().collect();
+        if parts.len() != 3 || parts[1] != constants::KW_AS {
+            return Err(MommyLangError::SyntaxError);
+        }
+        params.push((parts[0].to_string(), normalize_type(parts[2])?));
+    }
+
+    Ok(params)
+}
+
+fn normalize_type(raw_type: &str) -> Result<String, MommyLangError> {
+    match raw_type {
+        constants::TYPE_INT | constants::TYPE_FLOAT | constants::TYPE_ASCII | constants::TYPE_BOOL |
+        constants::TYPE_STRING | constants::C_KW_CHAR => Ok(raw_type.to_string()),
+        t if t == constants::KW_BOX || t == constants::KW_POINTER => Ok(constants::KW_POINTER.to_string()),
+        _ => Err(MommyLangError::TypeMismatch),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::tokens;
+
+    #[test]
+    fn chore_texts_are_freed_on_the_way_out() {
+        let mut symbols = SymbolTable::new();
+        let frame = start_function(&tokens("chore greet needs n as int gives int"), &mut symbols).unwrap();
+        declaration::create_variable(&tokens("mayihave \"hi\" in word as text"), &mut symbols).unwrap();
+
+        assert_eq!(
+            give_back(&tokens("giveback n + 1"), &symbols, &frame).unwrap(),
+            "{ int _mommy_result = n + 1; free(word); return _mommy_result; }",
+        );
+        assert!(end_function(frame, &mut symbols).ends_with("    free(word);\n}"));
+    }
+
+    #[test]
+    fn text_given_back_is_left_to_the_caller() {
+        let mut symbols = SymbolTable::new();
+        let frame = start_function(&tokens("chore name gives String"), &mut symbols).unwrap();
+        declaration::create_variable(&tokens("mayihave \"Ann\" in word as text"), &mut symbols).unwrap();
+        declaration::create_variable(&tokens("mayihave \"x\" in other as text"), &mut symbols).unwrap();
+
+        assert_eq!(
+            give_back(&tokens("giveback word"), &symbols, &frame).unwrap(),
+            "{ char* _mommy_result = word; free(other); return _mommy_result; }",
+        );
+    }
+}
@@ -6,27 +6,38 @@
 //! - String Literal: "say "string_literal"
 //! - Array Access: "say array_name in index"
 //! - Array wildcard for ASCII: "say array_name in ?"
+//! - Grid slot: "say grid_name in 1,2", whole row: "say grid_name in 1", everything: "say grid_name in ?"
 //! - Scalar Variable: "say variable_name_or_literal"
+//! - Choice: "say choice_variable" prints the member's name
+//! - Record: "say record_name" (every field on one line), "say record_name.field", "say array_name in index.field"
 //! 2. LISTEN
 //! -  Listen to variable input: "listen var_name"
 //! -  Listen with size limit: "listen var_name upto size"
 //!
 //! Notes:
-//! 1. Float, integer, and ASCII (unique int for string) types are supported.
+//! 1. Float, integer, bool, and ASCII (unique int for string) types are supported. A bool prints as yes or no.
 //! 2. For "say", if the variable is undeclared or type-mismatched, an error is returned.
 //! 3. For "listen", if the variable is undeclared or not a supported type, an error is returned.
+//! 4. "say array_name in i" checks i while the program runs when bounds checks are on.
+//! 5. Printing a whole row or grid loops with generated "_mommy_row" counters, never with a name you could own.
 //!
 //!
 //!
 //!
-use std::collections::HashMap;
 use crate::responses::MommyLangError;
+use crate::choice;
 use crate::constants;
+use crate::declaration;
+use crate::lang_enums::SymbolKind;
+use crate::record;
+use crate::symbol_table::{Symbol, SymbolTable};
+use crate::text;
 use crate::validate_syntax;
+use crate::syntax_lexer::{Token, TokenKind};
 
 pub fn say(
-    tokens: &Vec<String>,
-    symbols: &HashMap<String, String>
+    tokens: &[Token],
+    symbols: &SymbolTable
 ) -> Result<String, MommyLangError> {
 
     if validate_syntax::is_missing_say_args(tokens.len()) {
@@ -35,11 +46,11 @@ pub fn say(
 
     let message = &tokens[constants::IDX_IO_VALUE];
 
-    if message.starts_with("\"") {
+    if message.kind == TokenKind::Text {
         return say_literal(tokens);
     }
 
-    if tokens.len() >= constants::ARGS_MIN_IO_ARRAY && tokens[constants::IDX_IO_KEY_IN] == constants::KW_IN {
+    if tokens.len() >= constants::ARGS_MIN_IO_ARRAY && tokens[constants::IDX_IO_KEY_IN].is_keyword(constants::KW_IN) {
         return say_array(tokens, symbols);
     }
 
@@ -47,105 +58,171 @@ pub fn say(
 }
 
 
-fn say_literal(tokens: &Vec<String>) -> Result<String, MommyLangError> {
+fn say_literal(tokens: &[Token]) -> Result<String, MommyLangError> {
     // Syntax: say "string_literal"
-    let full_msg = tokens[constants::IDX_IO_VALUE..].join(constants::SYM_WHITESPACE);
-    let clean_msg = full_msg.trim_matches('"');
-    Ok(format!("printf(\"{}\\n\");", clean_msg))
+    if tokens.len() > constants::IDX_IO_VALUE + 1 {
+        return Err(MommyLangError::SyntaxError);
+    }
+
+    // The lexer already checked the escapes, only printf's own "%" needs doubling
+    let message = tokens[constants::IDX_IO_VALUE].as_str();
+    let inner = &message[1..message.len() - 1];
+    Ok(format!("printf(\"{}\\n\");", inner.replace('%', "%%")))
 }
 
 
 
 fn say_array(
-    tokens: &Vec<String>,
-    symbols: &HashMap<String, String>
+    tokens: &[Token],
+    symbols: &SymbolTable
 ) -> Result<String, MommyLangError> {
-    // Syntax: "say array_name in index"
-    let name = &tokens[constants::IDX_IO_VALUE];
-    let index = &tokens[constants::IDX_IO_ARR_IDX];
+    // Syntax: "say array_name in index", "say grid_name in row" prints the whole row
+    let name = tokens[constants::IDX_IO_VALUE].as_str();
+    let index = tokens[constants::IDX_IO_ARR_IDX].as_str();
 
-    let array_type = symbols.get(name).ok_or(MommyLangError::UndeclaredVariable)?;
+    let array = symbols.lookup(name)?;
 
-    if validate_syntax::is_type_mismatch(array_type) {
+    if validate_syntax::is_type_mismatch(array) {
         return Err(MommyLangError::TypeMismatch);
     }
 
-    let parts: Vec<&str> = array_type.split(constants::SYM_SPLITTER).collect();
+    let inner_type = array.data_type.as_str();
 
-
-    let inner_type = validate_syntax::select_inner_type(parts.len(), parts[constants::IDX_META_DATA_TYPE]);
-    let max_size = validate_syntax::select_max_size(parts.len(), parts[constants::IDX_META_SIZE]);
-
-    if max_size == 0{
+    if array.fixed_size() == Some(0) {
         return Err(MommyLangError::AccessViolation);
     }
 
-    if validate_syntax::is_kw_all(&index){
-         return match inner_type {
-            t if t == constants::TYPE_ASCII =>
-                Ok(format!("for (int i = 0; i < {}; i++) {{ printf(\"%c\", {}[i]); }} printf(\"\\n\");", max_size, name)),
-            t if t == constants::TYPE_FLOAT =>
-                Ok(format!("for (int i = 0; i < {}; i++) {{ printf(\"%f \", {}[i]); }} printf(\"\\n\");", max_size, name)),
-            t if t == constants::C_TYPE_CHAR_PTR || t == constants::TYPE_STRING =>
-                Ok(format!("for (int i = 0; i < {}; i++) {{ printf(\"%s \", {}[i]); }} printf(\"\\n\");", max_size, name)),
-            _ => Ok(format!("for (int i = 0; i < {}; i++) {{ printf(\"%d \", {}[i]); }} printf(\"\\n\");", max_size, name)),
-        };
-    }
-
-    if let Ok(idx_num) = index.parse::<usize>() {
-        if (array_type.starts_with(constants::KW_ARRAY) || array_type.starts_with(constants::KW_HEAP)) &&
-           idx_num >= max_size &&
-           max_size != constants::SIZE_UNKNOWN
-        {
-            return Err(MommyLangError::AccessViolation);
+    let (index, field) = record::split_field(index); // "say class in 2.name"
+    let parts = if validate_syntax::is_kw_all(index) { Vec::new() } else { declaration::split_index(index) };
+    for part in &parts {
+        if part.parse::<usize>().is_err() && !symbols.contains_key(part) {
+            // It's garbage (like "say arr in $#@")
+            return Err(MommyLangError::SyntaxError);
         }
     }
-    else if symbols.contains_key(index) {
 
+    let sides = declaration::array_sides(array);
+    let element = declaration::c_element_prefix(name, array, &parts, symbols)?;
+    if parts.len() == sides.len() {
+        return match field {
+            Some(field) => {
+                let field_type = array.field_type(field).ok_or(MommyLangError::UnknownField)?;
+                Ok(say_slot(field_type, &record::c_field(element, array, Some(field))?))
+            }
+            None if array.is_record() => Ok(say_record(&element, &array.fields)),
+            None if array.is_choice() => Ok(format!("printf(\"%s\\n\", {});", choice::c_name(&element, &array.members))),
+            None => Ok(say_slot(inner_type, &element)),
+        };
     }
-    else {
-        // It's garbage (like "say arr in $#@")
-        return Err(MommyLangError::SyntaxError);
+    if field.is_some() { // A field belongs to one slot, not to a whole row
+        return Err(MommyLangError::WrongIndexCount);
     }
 
+    // Fewer indices than sides: everything that is left, one row per line. The loops go by the live size.
+    let rest: Option<Vec<String>> = sides[parts.len()..].iter().cloned().collect();
+    let rest = rest.ok_or(MommyLangError::AccessViolation)?;
+    Ok(say_rows(array, &element, &rest))
+}
+
+fn say_slot(inner_type: &str, element: &str) -> String {
     match inner_type {
-        constants::TYPE_FLOAT => Ok(format!("printf(\"%f\\n\", {}[{}]);", name, index)),
+        constants::TYPE_FLOAT => format!("printf(\"%f\\n\", {});", element),
         t if t == constants::C_TYPE_CHAR_PTR || t == constants::TYPE_STRING =>
-            Ok(format!("printf(\"%s\\n\", {}[{}]);", name, index)),
+            format!("printf(\"%s\\n\", {});", element),
         t if t == constants::TYPE_ASCII =>
-            Ok(format!("printf(\"%c\\n\", {}[{}]);", name, index)),
-        _ => Ok(format!("printf(\"%d\\n\", {}[{}]);", name, index)),
+            format!("printf(\"%c\\n\", {});", element),
+        t if t == constants::TYPE_BOOL =>
+            format!("printf(\"%s\\n\", {} ? \"yes\" : \"no\");", element),
+        _ => format!("printf(\"%d\\n\", {});", element),
+    }
+}
+
+/// One generated counter per side, "_mommy_row0" for the innermost. A user's "i" is left alone,
+/// so "say grid in i" prints row i, not the diagonal.
+fn say_rows(array: &Symbol, prefix: &str, sides: &[String]) -> String {
+    let row = format!("{}{}", constants::C_ROW_PREFIX, sides.len().saturating_sub(1));
+    let slot = format!("{}[{}]", prefix, row);
+
+    if let [outer, rest @ ..] = sides && !rest.is_empty() {
+        let inner = say_rows(array, &slot, rest);
+        return format!("for (int {0} = 0; {0} < {1}; {0}++) {{ {2} }}", row, outer, inner);
+    }
+
+    let length = sides.first().map(String::as_str).unwrap_or_default();
+    let each = |body: String| format!("for (int {0} = 0; {0} < {1}; {0}++) {{ {2} }}", row, length, body);
+    if array.is_record() { // One record per line
+        return each(say_record(&slot, &array.fields));
+    }
+    if array.is_choice() {
+        return format!("{} printf(\"\\n\");", each(format!("printf(\"%s \", {});", choice::c_name(&slot, &array.members))));
+    }
+
+    let body = match array.data_type.as_str() {
+        t if t == constants::TYPE_ASCII => format!("printf(\"%c\", {});", slot),
+        t if t == constants::TYPE_FLOAT => format!("printf(\"%f \", {});", slot),
+        t if t == constants::C_TYPE_CHAR_PTR || t == constants::TYPE_STRING => format!("printf(\"%s \", {});", slot),
+        t if t == constants::TYPE_BOOL => format!("printf(\"%s \", {} ? \"yes\" : \"no\");", slot),
+        _ => format!("printf(\"%d \", {});", slot),
+    };
+    format!("{} printf(\"\\n\");", each(body))
+}
+
+/// Every field of a record on one line, "Ann 91.500000 7".
+fn say_record(element: &str, fields: &[(String, String)]) -> String {
+    let mut formats = Vec::new();
+    let mut values = Vec::new();
+    for (field, field_type) in fields {
+        let value = format!("{}{}{}", element, constants::SYM_DOT, field);
+        let (format, value) = match field_type.as_str() {
+            constants::TYPE_FLOAT => ("%f", value),
+            constants::TYPE_STRING => ("%s", value),
+            constants::TYPE_ASCII => ("%c", value),
+            constants::TYPE_BOOL => ("%s", format!("{} ? \"yes\" : \"no\"", value)),
+            _ => ("%d", value),
+        };
+        formats.push(format);
+        values.push(value);
     }
+    format!("printf(\"{}\\n\", {});", formats.join(constants::SYM_WHITESPACE), values.join(", "))
 }
 
 fn say_scalar(
-    tokens: &Vec<String>,
-    symbols: &HashMap<String, String>
+    tokens: &[Token],
+    symbols: &SymbolTable
 ) -> Result<String, MommyLangError> {
 
-    let name = &tokens[constants::IDX_IO_VALUE];
+    let name = tokens[constants::IDX_IO_VALUE].as_str();
 
-    if let Ok(_) = name.parse::<i32>() {
+    if name.parse::<i32>().is_ok() {
         return Ok(format!("printf(\"%d\\n\", {});", name));
     }
 
-    if let Ok(_) = name.parse::<f64>() {
+    if name.parse::<f64>().is_ok() {
         return Ok(format!("printf(\"%f\\n\", {});", name));
     }
 
-    let var_type = symbols.get(name).ok_or(MommyLangError::UndeclaredVariable)?;
+    let symbol = symbols.lookup(name)?;
+
+    match (symbol.kind, symbol.data_type.as_str()) {
+        (SymbolKind::Scalar, _) if symbol.is_record() =>
+            Ok(say_record(name, &symbol.fields)),
+
+        (SymbolKind::Scalar, _) if symbol.is_choice() =>
+            Ok(format!("printf(\"%s\\n\", {});", choice::c_name(name, &symbol.members))),
 
-    match var_type.as_str() {
-        t if t == constants::TYPE_FLOAT =>
+        (SymbolKind::Scalar, constants::TYPE_FLOAT) =>
             Ok(format!("printf(\"%f\\n\", {});", name)),
 
-        t if t == constants::TYPE_STRING || t == constants::C_TYPE_CHAR_PTR =>
+        (SymbolKind::Scalar, _) if symbol.is_text() =>
             Ok(format!("printf(\"%s\\n\", {});", name)),
 
-        t if t == constants::TYPE_ASCII =>
+        (SymbolKind::Scalar, constants::TYPE_ASCII) =>
             Ok(format!("printf(\"%c\\n\", {});", name)),
 
-        t if t == constants::KW_POINTER =>
+        (SymbolKind::Scalar, constants::TYPE_BOOL) =>
+            Ok(format!("printf(\"%s\\n\", {} ? \"yes\" : \"no\");", name)),
+
+        (SymbolKind::Pointer, _) =>
             Ok(format!("if ({0} == NULL) {{ printf(\"NULL\\n\"); }} else {{ printf(\"%d\\n\", *{0}); }}", name)),
 
         _ => Ok(format!("printf(\"%d\\n\", {});", name)),
@@ -153,8 +230,8 @@ fn say_scalar(
 }
 
 pub fn listen(
-    tokens: &Vec<String>,
-    symbols: &HashMap<String, String>
+    tokens: &[Token],
+    symbols: &SymbolTable
 ) -> Result<String, MommyLangError> {
     // Syntax: listen <var> [upto <size>]
 
@@ -162,38 +239,49 @@ pub fn listen(
         return Err(MommyLangError::MissingArguments);
     }
 
-    let name = &tokens[constants::IDX_IO_VALUE];
-    let var_type = symbols.get(name).ok_or(MommyLangError::UndeclaredVariable)?;
+    let name = tokens[constants::IDX_IO_VALUE].as_str();
+    let symbol = symbols.lookup(name)?;
+    validate_syntax::ensure_mutable(symbol)?;
 
-    let buffer_size = if tokens.len() >= constants::ARGS_MIN_IO_ARRAY && tokens[2] == constants::KW_UPTO {
-        tokens[3].clone()
+    let buffer_size = if tokens.len() >= constants::ARGS_MIN_IO_ARRAY && tokens[2].is_keyword(constants::KW_UPTO) {
+        tokens[3].to_string()
     } else {
-        get_size_from_type(var_type, "128")
+        symbol.size.clone().unwrap_or_else(|| "128".to_string())
     };
 
-    match var_type.as_str() {
-        t if t == constants::TYPE_INT => {
+    match (symbol.kind, symbol.data_type.as_str()) {
+        (SymbolKind::Scalar, constants::TYPE_TEXT) => Ok(text::listen(name)), // no buffer, it grows
+
+        (SymbolKind::Scalar, constants::TYPE_INT) => {
             Ok(format!(
                 "{{ char _mommy_buf[64]; if(fgets(_mommy_buf, 64, stdin)) {{ {} = atoi(_mommy_buf); }} }}",
                 name
             ))
         },
 
-        t if t == constants::TYPE_FLOAT => {
+        (SymbolKind::Scalar, constants::TYPE_FLOAT) => {
             Ok(format!(
                 "{{ char _mommy_buf[64]; if(fgets(_mommy_buf, 64, stdin)) {{ {} = atof(_mommy_buf); }} }}",
                 name
             ))
         },
 
-        t if t.contains(constants::TYPE_STRING) || t.contains(constants::C_TYPE_CHAR_PTR) => {
+        (SymbolKind::Scalar, constants::TYPE_BOOL) => { // "yes", "true" and "1" count, anything else is a no
+            Ok(format!(
+                "{{ char _mommy_buf[64]; if(fgets(_mommy_buf, 64, stdin)) {{ {} = (_mommy_buf[0] == 'y' || _mommy_buf[0] == 'Y' || \
+                _mommy_buf[0] == 't' || _mommy_buf[0] == 'T' || _mommy_buf[0] == '1'); }} }}",
+                name
+            ))
+        },
+
+        (SymbolKind::Scalar | SymbolKind::Array | SymbolKind::Heap, _) if symbol.is_text() => {
              Ok(format!(
                 "fgets({}, {}, stdin); {}[strcspn({}, \"\\n\")] = 0;",
                 name, buffer_size, name, name
             ))
         },
 
-        t if t.contains(constants::TYPE_ASCII) => {
+        (SymbolKind::Scalar | SymbolKind::Array | SymbolKind::Heap, constants::TYPE_ASCII) => {
             Ok(format!(
                 "{{ char _temp_ascii[{}]; \
                    if(fgets(_temp_ascii, {}, stdin)) {{ \
@@ -215,11 +303,48 @@ pub fn listen(
     }
 }
 
-fn get_size_from_type(type_str: &str, default: &str) -> String {
-    let parts: Vec<&str> = type_str.split(constants::SYM_SPLITTER).collect();
-    if parts.len() > 2 {
-        parts[2].to_string()
-    } else {
-        default.to_string()
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{declared, run, tokens};
+
+    #[test]
+    fn bool_prints_and_listens_as_yes_or_no() {
+        let symbols = declared(&["mayihave yes in finished as bool"]);
+        assert_eq!(say(&tokens("say finished"), &symbols).unwrap(), "printf(\"%s\\n\", finished ? \"yes\" : \"no\");");
+
+        let read = listen(&tokens("listen finished"), &symbols).unwrap();
+        assert!(read.contains("finished = (_mommy_buf[0] == 'y'"), "{}", read);
+    }
+
+    #[test]
+    fn grids_print_a_slot_a_row_or_everything() {
+        let symbols = declared(&["group 3,4 in board as int"]);
+        assert_eq!(say(&tokens("say board in 1,2"), &symbols).unwrap(), "printf(\"%d\\n\", board[1][2]);");
+
+        let row = say(&tokens("say board in 1"), &symbols).unwrap();
+        assert!(row.contains("board[1][_mommy_row0]"), "{}", row);
+        let every_row = say(&tokens("say board in ?"), &symbols).unwrap();
+        assert!(every_row.starts_with("for (int _mommy_row1 = 0; _mommy_row1 < 3; _mommy_row1++) {"), "{}", every_row);
+    }
+
+    #[test]
+    fn row_index_named_i_is_not_shadowed() {
+        let mut symbols = declared(&["group 3,3 in grid as int with 1, 2, 3, 4, 5, 6, 7, 8, 9", "mayihave 1 in i as int"]);
+
+        let c_code = run("say grid in i", &mut symbols).unwrap();
+        assert!(!c_code.contains("int i "), "the row loop declared its own i: {}", c_code);
+        assert!(c_code.contains("[i]"), "row i is not the one printed: {}", c_code);
+        assert!(c_code.contains("[_mommy_row0]"), "{}", c_code);
+    }
+
+    #[test]
+    fn whole_grid_gets_one_counter_per_side() {
+        let mut symbols = declared(&["group 2,3 in grid as float"]);
+
+        let c_code = run("say grid in ?", &mut symbols).unwrap();
+        assert!(c_code.contains("grid[_mommy_row1][_mommy_row0]"), "{}", c_code);
+        assert!(!c_code.contains("int i "), "{}", c_code);
     }
 }
@@ -2,5 +2,27 @@
 pub enum ScopeType {
     Loop,       // "punishme"
     Condition,  // "ask"
-    Alternative // "or" (We track this to prevent double 'or')
-}
\ No newline at end of file
+    Alternative, // "or" (We track this to prevent double 'or')
+    Function,   // "chore"
+    Pick,       // "pick", before its first arm
+    Arm,        // "when"
+    Otherwise,  // "otherwise" (the last arm, like the plain "or")
+    Placeholder, // a block whose first line failed, kept open so its body is still checked
+}
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SymbolKind {
+    Scalar,     // "mayihave"
+    Array,      // "group"
+    Heap,       // "ibegyou"
+    Pointer,    // "box"
+    Function,   // "chore"
+    Record,     // "record", the type itself
+    Choice,     // "choices", the type itself
+}
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Lifetime {
+    Live,            // "ibegyou", not given back yet (everything that is not heap just stays here)
+    Released(usize), // "takeitback", and the block depth it happened at
+    MaybeReleased,   // given back inside a block that might not have run
+    GivenAway,       // handed to the caller with "giveback", not ours to free
+}
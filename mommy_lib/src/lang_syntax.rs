@@ -19,21 +19,38 @@ pub enum MommyLangSyntax {
     LoopStartBasic, // punishme
     LoopEnd,        // done
     LoopBreak,      // satisfied
+    LoopSkip,       // skip
+    LoopStartFirst, // punishmefirst
+    LoopEndAgain,   // againif
     Condition,      // ask
-    ConditionElse,  // or
+    ConditionElse,  // or, or ask if
     ProgramEnd,     // leave
     Array,          // group
     Unknown,        // ???
     LoopStartCondition, // punishmeif
     String,         // ascii
     Malloc,         //ibegyou
+    MallocResize,   // ibegyoumore
     FreeMalloc,     // takeitback
     ReadInput,      // listen
     IncludeLib,     // makeme
+    FunctionStart,  // chore
+    FunctionReturn, // giveback
+    FunctionCall,   // dothis
+    Conversion,     // turn
+    TextJoin,       // join
+    TextLength,     // measure
+    TextSlice,      // slice
+    Record,         // record
+    Choices,        // choices
+    Constant,       // always
+    Pick,           // pick
+    PickArm,        // when
+    PickOtherwise,  // otherwise
 }
 
 impl MommyLangSyntax {
-    pub fn from_str(token: &str) -> Self {
+    pub fn from_word(token: &str) -> Self {
         match token.trim() {
             "mayihave" => MommyLangSyntax::Declaration,
             "replace" => MommyLangSyntax::Assignment,
@@ -42,6 +59,9 @@ impl MommyLangSyntax {
             "punishme" => MommyLangSyntax::LoopStartBasic,
             "done" => MommyLangSyntax::LoopEnd,
             "satisfied" => MommyLangSyntax::LoopBreak,
+            "skip" => MommyLangSyntax::LoopSkip,
+            "punishmefirst" => MommyLangSyntax::LoopStartFirst,
+            "againif" => MommyLangSyntax::LoopEndAgain,
             "ask" => MommyLangSyntax::Condition,
             "or" => MommyLangSyntax::ConditionElse,
             "leave" => MommyLangSyntax::ProgramEnd,
@@ -49,11 +69,40 @@ impl MommyLangSyntax {
             "punishmeif" => MommyLangSyntax::LoopStartCondition,
             "ascii" => MommyLangSyntax::String,
             "ibegyou" => MommyLangSyntax::Malloc,
+            "ibegyoumore" => MommyLangSyntax::MallocResize,
             "takeitback" => MommyLangSyntax::FreeMalloc,
             "listen" => MommyLangSyntax::ReadInput,
             "makeme" => MommyLangSyntax::IncludeLib,
+            "chore" => MommyLangSyntax::FunctionStart,
+            "giveback" => MommyLangSyntax::FunctionReturn,
+            "dothis" => MommyLangSyntax::FunctionCall,
+            "turn" => MommyLangSyntax::Conversion,
+            "join" => MommyLangSyntax::TextJoin,
+            "measure" => MommyLangSyntax::TextLength,
+            "slice" => MommyLangSyntax::TextSlice,
+            "record" => MommyLangSyntax::Record,
+            "choices" => MommyLangSyntax::Choices,
+            "always" => MommyLangSyntax::Constant,
+            "pick" => MommyLangSyntax::Pick,
+            "when" => MommyLangSyntax::PickArm,
+            "otherwise" => MommyLangSyntax::PickOtherwise,
             _ => MommyLangSyntax::Unknown,
         }
     }
+
+    /// The statements that need a "done" (or an "againif") later on.
+    pub fn opens_block(&self) -> bool {
+        matches!(self, MommyLangSyntax::Condition | MommyLangSyntax::LoopStartBasic | MommyLangSyntax::LoopStartCondition |
+            MommyLangSyntax::LoopStartFirst | MommyLangSyntax::Pick | MommyLangSyntax::FunctionStart)
+    }
+
+    /// "or", "when" and "otherwise" start the next branch of a block that is already open.
+    pub fn continues_block(&self) -> bool {
+        matches!(self, MommyLangSyntax::ConditionElse | MommyLangSyntax::PickArm | MommyLangSyntax::PickOtherwise)
+    }
+
+    pub fn closes_block(&self) -> bool {
+        matches!(self, MommyLangSyntax::LoopEnd | MommyLangSyntax::LoopEndAgain)
+    }
 }
 
@@ -1,5 +1,8 @@
 pub mod syntax_lexer;
+pub mod diagnostic;
 pub mod alu;
+pub mod expression;
+pub mod functions;
 pub mod declaration;
 pub mod loops;
 pub mod conditions;
@@ -14,4 +17,14 @@ pub mod config;
 pub mod shell_format;
 pub mod package;
 pub mod validate_syntax;
+pub mod symbol_table;
+pub mod text;
+pub mod type_checker;
+pub mod lifetime;
+pub mod record;
+pub mod choice;
+pub mod pick;
+
+#[cfg(test)]
+mod test_support;
 
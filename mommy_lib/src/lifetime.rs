@@ -0,0 +1,156 @@
+//!
+//!
+//! This is the heap lifetime crate of mommylang.
+//!
+//! Every "ibegyou" block is followed from the line that begged for it to the line that gives it back.
+//!
+//! Checked:
+//! - "takeitback" on something that is not an "ibegyou" block (a group, a box, a variable) is an error.
+//! - "takeitback" twice on the same block is an error.
+//! - Using a block after "takeitback" is an error.
+//! - A block never given back is a warning, reported at the line that begged for it.
+//! - A block given back inside an "ask" or a loop might not have been given back at all,
+//!   so using it afterwards is a warning, not an error.
+//!
+//! Note:
+//! 1. The block rules live in the symbol table, since that is where blocks open and close.
+//! 2. "giveback" of a block hands it to the caller, so the chore does not leak it.
+//! 3. With "--track-heap", the C program also keeps a list of its blocks and reports the ones left at "leave".
+//!
+//!
+use crate::constants;
+use crate::lang_enums::{Lifetime, SymbolKind};
+use crate::lang_syntax::MommyLangSyntax;
+use crate::responses::{MommyLangError, MommyLangWarning};
+use crate::symbol_table::SymbolTable;
+use crate::syntax_lexer::{Token, TokenKind};
+
+// ================================================================
+// PUBLIC FUNCTIONS (The Logic)
+// ================================================================
+
+pub fn check_statement(
+    command: &MommyLangSyntax,
+    tokens: &[Token],
+    symbols: &mut SymbolTable
+) -> Result<(), MommyLangError> {
+    // "ibegyou" makes a new block and "takeitback" checks its own block in release()
+    if matches!(command, MommyLangSyntax::Malloc | MommyLangSyntax::FreeMalloc) {
+        return Ok(());
+    }
+
+    for name in mentioned_names(tokens) {
+        let Some(symbol) = symbols.get(&name) else { continue };
+        if symbol.kind != SymbolKind::Heap {
+            continue;
+        }
+
+        match symbol.lifetime {
+            Lifetime::Released(_) => return Err(MommyLangError::UseAfterFree),
+            Lifetime::MaybeReleased => symbols.warn(MommyLangWarning::MaybeReleased(name)),
+            Lifetime::Live if matches!(command, MommyLangSyntax::FunctionReturn) => {
+                if let Some(symbol) = symbols.get_mut(&name) {
+                    symbol.lifetime = Lifetime::GivenAway;
+                }
+            }
+            Lifetime::Live | Lifetime::GivenAway => {}
+        }
+    }
+    Ok(())
+}
+
+/// "takeitback": only a live "ibegyou" block can be given back.
+pub fn release(name: &str, symbols: &mut SymbolTable) -> Result<(), MommyLangError> {
+    let symbol = symbols.lookup(name)?;
+    if symbol.kind != SymbolKind::Heap {
+        return Err(MommyLangError::NotHeapMemory);
+    }
+
+    match symbol.lifetime {
+        Lifetime::Released(_) => return Err(MommyLangError::DoubleFree),
+        Lifetime::MaybeReleased => symbols.warn(MommyLangWarning::MaybeReleased(name.to_string())),
+        Lifetime::Live | Lifetime::GivenAway => {}
+    }
+
+    let depth = symbols.depth();
+    if let Some(symbol) = symbols.get_mut(name) {
+        symbol.lifetime = Lifetime::Released(depth);
+    }
+    Ok(())
+}
+
+// ================================================================
+// HELPERS
+// ================================================================
+
+/// Every name a statement touches, including the ones inside "fib(p)" or "p in i". Quotes are skipped.
+fn mentioned_names(tokens: &[Token]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let words = tokens.iter()
+        .skip(constants::IDX_SYNTAX_DEF_INITIATE + 1)
+        .filter(|token| token.kind != TokenKind::Text)
+        .flat_map(|token| token.text.split(|c: char| !c.is_alphanumeric() && c != '_'));
+
+    for word in words {
+        if !word.is_empty() && !names.iter().any(|name| name == word) { // One warning per name is plenty
+            names.push(word.to_string());
+        }
+    }
+    names
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{declared, run};
+
+    fn leaks(symbols: &SymbolTable) -> Vec<(usize, String)> {
+        symbols.warnings().iter()
+            .filter_map(|(line, warning)| match warning {
+                MommyLangWarning::HeapLeak(name) => Some((*line, name.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_block_is_given_back_once() {
+        let mut symbols = declared(&["ibegyou 3 in p as int", "mayihave 1 in x as int"]);
+        assert_eq!(run("takeitback x", &mut symbols), Err(MommyLangError::NotHeapMemory));
+        assert_eq!(run("takeitback p", &mut symbols).unwrap(), "free(p); p = NULL;");
+        assert_eq!(run("takeitback p", &mut symbols), Err(MommyLangError::DoubleFree));
+        assert_eq!(run("replace p in 0 with 1", &mut symbols), Err(MommyLangError::UseAfterFree));
+    }
+
+    #[test]
+    fn forgotten_blocks_are_warned_at_their_line() {
+        let mut symbols = SymbolTable::new();
+        symbols.set_line(2);
+        run("ibegyou 3 in kept as int", &mut symbols).unwrap();
+        symbols.set_line(3);
+        run("ibegyou 3 in returned as int", &mut symbols).unwrap();
+        run("takeitback returned", &mut symbols).unwrap();
+        symbols.check_leaks();
+        assert_eq!(leaks(&symbols), vec![(2, "kept".to_string())]);
+    }
+
+    #[test]
+    fn given_back_in_a_block_might_not_have_happened() {
+        let mut symbols = declared(&["ibegyou 3 in p as int"]);
+        symbols.push_scope();
+        run("takeitback p", &mut symbols).unwrap();
+        symbols.pop_scope();
+        run("replace p in 0 with 1", &mut symbols).unwrap();
+        assert!(matches!(symbols.warnings(), [(_, MommyLangWarning::MaybeReleased(name))] if name == "p"));
+
+        // Both branches of one "ask" give it back: only one ever runs
+        let mut symbols = declared(&["ibegyou 3 in q as int"]);
+        symbols.push_scope();
+        run("takeitback q", &mut symbols).unwrap();
+        symbols.switch_branch();
+        run("takeitback q", &mut symbols).unwrap();
+        symbols.pop_scope();
+        assert!(symbols.warnings().is_empty());
+    }
+}
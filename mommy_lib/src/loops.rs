@@ -2,21 +2,193 @@
 //!
 //! This is the loop crate of  mommylang.
 //!
-//! Language Syntax for (for (int i = 0; i < {}; i++)): "punishme count/variable"
-//! Language Syntax for (while (condition)): "punishmeif var1 operator var2"
-//! 
-//! Known Issues:
-//! 1. The "punishme" loop does not support nested loop as it only uses 1 variable "i" as the loop counter. If you want to do nested loops, you can use "punishmeif" with a condition that depends on the outer loop variable,
-//!    such as "punishmeif i < 10 && j < 5", but it might get messy.(I recommend to stick to "punishmeif" for nested loops.
-//! 2. Why there are no loop checks here lol?
-//! 
-//! 
+//! Language Syntax for (for (int i = 0; i < {}; i++)): "punishme count/expression"
+//! Language Syntax for a named counter: "punishme count as row"
+//! Language Syntax for a range: "punishme row from 2 to 20 step 2" (step is optional, "to" is inclusive)
+//! Language Syntax for (while (condition)): "punishmeif condition"
+//! Language Syntax for (do { } while (condition);): "punishmefirst" ... "againif condition"
+//! Language Syntax for a named loop: "punishme 10 as row named outer" (any loop header can end with "named <LABEL>")
+//! Language Syntax for (continue): "skip", or "skip outer" for a named loop
+//! Language Syntax for (break): "satisfied", or "satisfied outer" to leave a named loop from inside another one
+//!
+//! Notes:
+//! 1. Every "punishme" gets its own counter, so loops can be nested. Anonymous counters are named
+//!    by nesting level: "i" for the outer loop, then "j", "k" and so on. If you already have a variable
+//!    with that name, the counter gets a generated name instead of shadowing yours, and mommy warns you:
+//!    inside that loop, "i" is still your own variable, not the counter.
+//! 2. The counter is a registered int inside the loop body, and it is gone after "done" like any other
+//!    variable declared in the loop.
+//! 3. The counter is an int, so a float step is a type mismatch (the type checker says so). A step that works out
+//!    to 0, like "0" or "(1 - 1)", is rejected. A variable step decides the direction at runtime.
+//! 4. "satisfied" inside a "pick" jumps to a label right after the loop, since a C break would only leave the pick.
+//!    "satisfied outer" and "skip outer" jump the same way, to a label after or at the end of the named loop.
+//! 5. A text declared in the body is freed at the end of every round, and before "skip" or "satisfied" jumps
+//!    out of the rooms it lives in. Otherwise every round would leave its own copy behind.
+//! 6. A "punishmefirst" loop is closed by "againif", not "done". Its body always runs once, and the condition
+//!    cannot see what the body declared, since that room is already gone.
+//!
+//!
 
+use crate::symbol_table::SymbolTable;
 use crate::constants;
+use crate::expression;
+use crate::responses::{MommyLangError, MommyLangWarning};
+use crate::validate_syntax;
+use crate::syntax_lexer::Token;
+
+const LOOP_COUNTER_NAMES: [&str; 6] = ["i", "j", "k", "l", "m", "n"];
 
-pub fn for_loop(tokens: &Vec<String>) -> String {
-    let repeat_count = &tokens[constants::IDX_LOOP_COUNT];
-    format!("for (int i = 0; i < {}; i++) {{", repeat_count)
+/// A loop that is still open. It only gets its C labels when something has to jump to them.
+pub struct LoopFrame {
+    id: usize,
+    depth: usize, // The symbol table room of the body
+    label: Option<String>,
+    runs_first: bool, // "punishmefirst", closed by "againif"
+    jumped_out: bool,
+    skipped_to: bool,
+}
+
+impl LoopFrame {
+    pub fn new(id: usize, depth: usize, label: Option<String>, runs_first: bool) -> Self {
+        LoopFrame { id, depth, label, runs_first, jumped_out: false, skipped_to: false }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn is_named(&self, name: &str) -> bool {
+        self.label.as_deref() == Some(name)
+    }
+
+    pub fn runs_first(&self) -> bool {
+        self.runs_first
+    }
+}
+
+/// Takes "named <LABEL>" off the end of a loop header. The rest of the header is parsed as before.
+pub fn split_label(tokens: &[Token], open_loops: &[LoopFrame]) -> Result<(Vec<Token>, Option<String>), MommyLangError> {
+    let Some(named_index) = tokens.iter().position(|t| t.is_keyword(constants::KW_NAMED)) else {
+        return Ok((tokens.to_vec(), None));
+    };
+    if named_index + 2 != tokens.len() {
+        return Err(MommyLangError::SyntaxError);
+    }
+
+    let label = tokens[named_index + 1].as_str();
+    validate_syntax::ensure_valid_name(label)?;
+    if open_loops.iter().any(|frame| frame.is_named(label)) { // "satisfied outer" has to know which one
+        return Err(MommyLangError::VariableAlreadyExists);
+    }
+    Ok((tokens[..named_index].to_vec(), Some(label.to_string())))
+}
+
+/// Returns the C loop header and the name of the counter.
+/// The compiler registers the counter once the loop's own scope is open.
+pub fn for_loop(
+    tokens: &[Token],
+    symbols: &mut SymbolTable,
+    level: usize
+) -> Result<(String, String), MommyLangError> {
+
+    if tokens.len() < constants::ARGS_MIN_LEN {
+        return Err(MommyLangError::MissingArguments);
+    }
+
+    if tokens.get(constants::IDX_LOOP_KEY_FROM).is_some_and(|t| t.is_keyword(constants::KW_FROM)) {
+        return range_loop(tokens, symbols);
+    }
+
+    // Syntax: punishme <COUNT> [as <NAME>]
+    let as_index = tokens.iter().position(|t| t.is_keyword(constants::KW_AS));
+    let count = expression::parse_operand(&tokens[constants::IDX_LOOP_COUNT..as_index.unwrap_or(tokens.len())], symbols)?;
+
+    let counter = match as_index {
+        Some(idx) if idx + 2 == tokens.len() => {
+            let name = tokens[idx + 1].as_str();
+            validate_syntax::ensure_valid_name(name)?;
+            validate_syntax::ensure_var_new(name, symbols)?;
+            name.to_string()
+        }
+        Some(_) => return Err(MommyLangError::SyntaxError),
+        None => {
+            let counter = anonymous_counter(level, symbols);
+            let classic = classic_counter(level);
+            if counter != classic { // Never quietly: inside this loop, their "i" is not the counter
+                symbols.warn(MommyLangWarning::CounterRenamed(classic, counter.clone()));
+            }
+            counter
+        }
+    };
+
+    Ok((format!("for (int {0} = 0; {0} < {1}; {0}++) {{", counter, count), counter))
+}
+
+/// The name the counter of this header would get, without checking anything else.
+/// For a loop whose header failed, so its body can still use the counter.
+pub fn counter_name(tokens: &[Token], symbols: &SymbolTable, level: usize) -> Option<String> {
+    if tokens.get(constants::IDX_LOOP_KEY_FROM).is_some_and(|t| t.is_keyword(constants::KW_FROM)) {
+        return tokens.get(constants::IDX_LOOP_COUNT).map(Token::to_string);
+    }
+    match tokens.iter().position(|t| t.is_keyword(constants::KW_AS)) {
+        Some(idx) => tokens.get(idx + 1).map(Token::to_string),
+        None => Some(anonymous_counter(level, symbols)),
+    }
+}
+
+fn range_loop(
+    tokens: &[Token],
+    symbols: &SymbolTable
+) -> Result<(String, String), MommyLangError> {
+    // Syntax: punishme <NAME> from <START> to <END> [step <STEP>]
+
+    let counter = tokens[constants::IDX_LOOP_COUNT].as_str();
+    validate_syntax::ensure_valid_name(counter)?;
+    validate_syntax::ensure_var_new(counter, symbols)?;
+
+    let to_index = tokens.iter().position(|t| t.is_keyword(constants::KW_TO)).ok_or(MommyLangError::SyntaxError)?;
+    let step_index = tokens.iter().position(|t| t.is_keyword(constants::KW_STEP));
+    let end_stop = step_index.unwrap_or(tokens.len());
+
+    if to_index > end_stop {
+        return Err(MommyLangError::SyntaxError);
+    }
+
+    let start = expression::parse_operand(&tokens[constants::IDX_LOOP_KEY_FROM + 1..to_index], symbols)?;
+    let end = expression::parse_operand(&tokens[to_index + 1..end_stop], symbols)?;
+    let (step, fixed) = match step_index {
+        Some(idx) => {
+            let step = expression::parse_operand(&tokens[idx + 1..], symbols)?;
+            (step.to_string(), step.constant())
+        }
+        None => (constants::LOOP_DEFAULT_STEP.to_string(), Some(1.0)),
+    };
+
+    // Literal steps pick the direction now, variable steps pick it at runtime
+    let condition = match fixed {
+        Some(value) if value > 0.0 => format!("{} <= {}", counter, end),
+        Some(value) if value < 0.0 => format!("{} >= {}", counter, end),
+        Some(_) => return Err(MommyLangError::InvalidLoopStep), // step 0 never ends
+        None => format!("({2} > 0) ? {0} <= {1} : {0} >= {1}", counter, end, step),
+    };
+
+    Ok((format!("for (int {0} = {1}; {2}; {0} += {3}) {{", counter, start, condition, step), counter.to_string()))
+}
+
+fn anonymous_counter(level: usize, symbols: &SymbolTable) -> String {
+    let classic = classic_counter(level);
+    if symbols.contains_key(&classic) { // The user already owns that name, don't shadow it
+        return format!("{}{}", constants::LOOP_COUNTER_PREFIX, level);
+    }
+    classic
+}
+
+/// "i" for the outer loop, then "j", "k" and so on.
+fn classic_counter(level: usize) -> String {
+    match LOOP_COUNTER_NAMES.get(level) {
+        Some(name) => name.to_string(),
+        None => format!("{}{}", LOOP_COUNTER_NAMES[0], level),
+    }
 }
 
 pub fn done() -> String {
@@ -24,12 +196,61 @@ pub fn done() -> String {
 }
 
 pub fn satisfied() -> String {
-    format!("break;")
+    "break;".to_string()
+}
+
+/// "satisfied" from inside a pick, which would swallow a plain break, or "satisfied outer".
+pub fn satisfied_from(frame: &mut LoopFrame) -> String {
+    frame.jumped_out = true;
+    format!("goto {};", loop_label(constants::C_LOOP_EXIT_PREFIX, frame.id))
+}
+
+pub fn skip() -> String {
+    "continue;".to_string()
+}
+
+/// "skip outer": straight to the end of the named loop's body, so it moves on to its next round.
+pub fn skip_from(frame: &mut LoopFrame) -> String {
+    frame.skipped_to = true;
+    format!("goto {};", loop_label(constants::C_LOOP_NEXT_PREFIX, frame.id))
+}
+
+/// "done" for a loop. The labels only show up when something inside jumped to them.
+pub fn end_loop(frame: LoopFrame) -> String {
+    close_loop(&frame, done())
+}
+
+pub fn do_while() -> String {
+    "do {".to_string()
+}
+
+/// "againif <CONDITION>" closes a "punishmefirst" loop.
+pub fn again_if(tokens: &[Token], frame: LoopFrame, symbols: &SymbolTable) -> Result<String, MommyLangError> {
+    if tokens.len() < constants::ARGS_MIN_LEN {
+        return Err(MommyLangError::MissingArguments);
+    }
+    let condition = expression::compile_condition(&tokens[1..], symbols)?;
+    Ok(close_loop(&frame, format!("}} {} ({});", constants::C_KW_WHILE, condition)))
+}
+
+fn close_loop(frame: &LoopFrame, closing: String) -> String {
+    let mut c_code = closing;
+    if frame.skipped_to {
+        c_code = format!("{}: ; {}", loop_label(constants::C_LOOP_NEXT_PREFIX, frame.id), c_code);
+    }
+    if frame.jumped_out {
+        c_code = format!("{} {}: ;", c_code, loop_label(constants::C_LOOP_EXIT_PREFIX, frame.id));
+    }
+    c_code
+}
+
+fn loop_label(prefix: &str, id: usize) -> String {
+    format!("{}{}", prefix, id)
 }
 
 
-pub fn while_loop(tokens: &Vec<String>) -> String{
+pub fn while_loop(tokens: &[Token], symbols: &SymbolTable) -> Result<String, MommyLangError> {
 
-    let condition = tokens[1..].join(constants::SYM_WHITESPACE);
-    format!("while ({}) {{", condition)
+    let condition = expression::compile_condition(&tokens[1..], symbols)?;
+    Ok(format!("while ({}) {{", condition))
 }
\ No newline at end of file
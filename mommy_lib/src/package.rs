@@ -16,8 +16,10 @@ use crate::responses::MommyLangError;
 pub fn add_package(package: &str) -> Result<String, MommyLangError> {
 
     match package.trim() {
-        "listen_and_read" => Ok(format!("{}", package_list::CStandardPackages::InputOutput.to_string())),
-        "ask_more" => Ok(format!("{}", package_list::CStandardPackages::Utilities.to_string())),
+        "listen_and_read" => Ok(package_list::CStandardPackages::InputOutput.to_string()),
+        "ask_more" => Ok(package_list::CStandardPackages::Utilities.to_string()),
+        "words" => Ok(package_list::MommyCustomPackages::Words.to_string()),
+        "guard" => Ok(package_list::MommyCustomPackages::Guard.to_string()),
         _ => Err(MommyLangError::UnknownPackage),
     }
 }
\ No newline at end of file
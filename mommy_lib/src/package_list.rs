@@ -20,7 +20,8 @@ pub enum CStandardPackages{
 
 
 pub enum MommyCustomPackages{
-    // tumbleweed
+    Words, // the managed "text" runtime, added on its own when text is used
+    Guard, // runtime safety checks, added on its own when a check is emitted
 }
 
 impl fmt::Display for CStandardPackages {
@@ -37,4 +38,171 @@ impl fmt::Display for CStandardPackages {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl fmt::Display for MommyCustomPackages {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MommyCustomPackages::Words => write!(f, "{}", WORDS_RUNTIME),
+            MommyCustomPackages::Guard => write!(f, "{}", GUARD_RUNTIME),
+        }
+    }
+}
+
+/// A bad index stops the program with exit code 3, a zero divisor with 4 and a heap size below 1 with 5,
+/// so none of them is mistaken for a normal failure.
+/// The divisor check comes once per type, so int division stays int division. Plain C, no compiler extensions.
+/// The heap tracker only reports, at "leave". It never stops the program.
+const GUARD_RUNTIME: &str = r#"#include <stdio.h>
+#include <stdlib.h>
+
+static int _mommy_guard_index(int index, int size, int line) {
+    if (index < 0 || index >= size) {
+        printf("Mommy Error: Line %d, you reached for shelf %d but there are only %d. Hands where I can see them.\n", line, index, size);
+        exit(3);
+    }
+    return index;
+}
+
+static void _mommy_guard_zero(const char* name, int line) {
+    printf("Mommy Error: Line %d, '%s' is zero. You cannot split things into nothing.\n", line, name);
+    exit(4);
+}
+
+static inline int _mommy_guard_divisor(int divisor, const char* name, int line) {
+    if (divisor == 0) _mommy_guard_zero(name, line);
+    return divisor;
+}
+
+static inline double _mommy_guard_divisor_float(double divisor, const char* name, int line) {
+    if (divisor == 0) _mommy_guard_zero(name, line);
+    return divisor;
+}
+
+static int _mommy_guard_size(int size, const char* name, int line) {
+    if (size < 1) {
+        printf("Mommy Error: Line %d, you begged for %d slots for '%s'. Beg for at least one.\n", line, size, name);
+        exit(5);
+    }
+    return size;
+}
+
+#define _MOMMY_GUARD_MAX_BLOCKS 256
+
+static struct { void* block; const char* name; int line; } _mommy_guard_blocks[_MOMMY_GUARD_MAX_BLOCKS];
+static int _mommy_guard_block_count = 0;
+
+static void _mommy_guard_track(void* block, const char* name, int line) {
+    if (_mommy_guard_block_count >= _MOMMY_GUARD_MAX_BLOCKS) return;
+    _mommy_guard_blocks[_mommy_guard_block_count].block = block;
+    _mommy_guard_blocks[_mommy_guard_block_count].name = name;
+    _mommy_guard_blocks[_mommy_guard_block_count].line = line;
+    _mommy_guard_block_count++;
+}
+
+static void _mommy_guard_untrack(void* block) {
+    for (int i = 0; i < _mommy_guard_block_count; i++) {
+        if (_mommy_guard_blocks[i].block == block) {
+            _mommy_guard_blocks[i] = _mommy_guard_blocks[--_mommy_guard_block_count];
+            return;
+        }
+    }
+}
+
+static void _mommy_guard_report(void) {
+    for (int i = 0; i < _mommy_guard_block_count; i++) {
+        printf("Mommy Warning: '%s' from line %d was never given back.\n", _mommy_guard_blocks[i].name, _mommy_guard_blocks[i].line);
+    }
+}"#;
+
+/// Every text is its own heap copy. Setting, joining or slicing frees the old one first.
+const WORDS_RUNTIME: &str = r#"#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static char* _mommy_text_alloc(size_t size) {
+    char* text = (char*)malloc(size);
+    if (text == NULL) { printf("Mommy Error: No memory for text\n"); exit(1); }
+    return text;
+}
+
+static char* _mommy_text_new(const char* src) {
+    if (src == NULL) src = "";
+    size_t len = strlen(src);
+    char* text = _mommy_text_alloc(len + 1);
+    memcpy(text, src, len + 1);
+    return text;
+}
+
+static void _mommy_text_set(char** target, const char* src) {
+    char* copy = _mommy_text_new(src); /* src might be *target itself */
+    free(*target);
+    *target = copy;
+}
+
+static void _mommy_text_append(char** target, const char* src) {
+    if (src == NULL) src = "";
+    size_t old_len = strlen(*target);
+    size_t add_len = strlen(src);
+    char* joined = _mommy_text_alloc(old_len + add_len + 1);
+    memcpy(joined, *target, old_len);
+    memcpy(joined + old_len, src, add_len + 1);
+    free(*target);
+    *target = joined;
+}
+
+static int _mommy_text_length(const char* src) {
+    return src == NULL ? 0 : (int)strlen(src);
+}
+
+static int _mommy_text_compare(const char* a, const char* b) {
+    return strcmp(a == NULL ? "" : a, b == NULL ? "" : b);
+}
+
+static void _mommy_text_slice(char** target, const char* src, int start, int end) {
+    int len = (int)strlen(src);
+    if (start < 0) start = 0;
+    if (start > len) start = len;
+    if (end >= len) end = len - 1;
+    int count = end - start + 1;
+    if (count < 0) count = 0;
+    char* part = _mommy_text_alloc((size_t)count + 1);
+    memcpy(part, src + start, (size_t)count);
+    part[count] = '\0';
+    free(*target);
+    *target = part;
+}
+
+static void _mommy_text_read(char** target) {
+    size_t cap = 64, len = 0;
+    char* line = _mommy_text_alloc(cap);
+    int c;
+    while ((c = getchar()) != EOF && c != '\n') {
+        if (len + 1 >= cap) {
+            char* bigger = (char*)realloc(line, cap * 2);
+            if (bigger == NULL) { printf("Mommy Error: No memory for text\n"); exit(1); }
+            line = bigger;
+            cap *= 2;
+        }
+        line[len++] = (char)c;
+    }
+    line[len] = '\0';
+    free(*target);
+    *target = line;
+}
+
+static void _mommy_text_to_ascii(const char* src, int* dest, int size) {
+    int i = 0;
+    for (; i < size && src[i] != '\0'; i++) dest[i] = (unsigned char)src[i];
+    for (; i < size; i++) dest[i] = 0;
+}
+
+static void _mommy_text_from_ascii(char** target, const int* src, int size) {
+    int len = 0;
+    while (len < size && src[len] != 0) len++;
+    char* text = _mommy_text_alloc((size_t)len + 1);
+    for (int i = 0; i < len; i++) text[i] = (char)src[i];
+    text[len] = '\0';
+    free(*target);
+    *target = text;
+}"#;
@@ -0,0 +1,171 @@
+//!
+//!
+//! This is the pick (switch) crate of mommylang.
+//!
+//! Language Syntax: "pick value"
+//! Arm Syntax: "when 1", "when 2, 3", "when 'q'", "when angry"
+//! Default Syntax: "otherwise"
+//! A whole pick needs one "done" at the end, the arms do not get their own.
+//!
+//! Note:
+//! 1. The value being picked is an int, char, ascii or a choice. Floats, texts and bools are rejected.
+//!
+//! 2. An arm value has to be known before the program runs: a literal, a char, a constant or a choice member.
+//!    It must fit the value being picked, so a mood cannot have a "when 2" and an int cannot have a "when angry".
+//!
+//! 3. Every arm stops on its own, there is no falling into the next arm. "when 2, 3" shares one arm.
+//!
+//! 4. The same value twice is rejected, even when it is spelled differently ("when 65" and "when 'A'", "when 10" and "when '\n'").
+//!
+//! 5. "otherwise" is optional and has to be the last arm. Nothing can sit between "pick" and its first arm.
+//!
+//! 6. "satisfied" inside a pick still leaves the loop around it, not just the pick.
+//!
+//!
+use crate::constants;
+use crate::declaration;
+use crate::expression;
+use crate::responses::MommyLangError;
+use crate::symbol_table::SymbolTable;
+use crate::syntax_lexer::{self, Token};
+
+/// The pick we are currently inside. Remembers what it looks at and which values already have an arm.
+pub struct PickFrame {
+    subject: Vec<Token>,
+    seen: Vec<String>,
+    has_arm: bool,
+}
+
+// ================================================================
+// PUBLIC FUNCTIONS (The Logic)
+// ================================================================
+
+pub fn start_pick(
+    tokens: &[Token],
+    symbols: &SymbolTable
+) -> Result<(String, PickFrame), MommyLangError> {
+    // Syntax: pick <VALUE>
+
+    if tokens.len() < constants::ARGS_MIN_LEN {
+        return Err(MommyLangError::MissingArguments);
+    }
+
+    let subject = tokens[constants::IDX_PICK_VALUE..].to_vec();
+    let value = expression::parse_value(&subject, symbols)?; // The type checker already made sure it can be picked
+
+    let frame = PickFrame { subject, seen: Vec::new(), has_arm: false };
+    Ok((format!("{} ({}) {{", constants::C_KW_SWITCH, value), frame))
+}
+
+pub fn when(
+    tokens: &[Token],
+    frame: &mut PickFrame,
+    symbols: &SymbolTable
+) -> Result<String, MommyLangError> {
+    // Syntax: when <VALUE>[, <VALUE> ...]
+
+    if tokens.len() < constants::ARGS_MIN_LEN {
+        return Err(MommyLangError::MissingArguments);
+    }
+
+    let mut labels = Vec::new();
+    for value in declaration::split_initializer(&tokens[constants::IDX_PICK_VALUE..]) {
+        if value.text.is_empty() { // "when 1, , 2"
+            return Err(MommyLangError::SyntaxError);
+        }
+
+        // Same rules as "ask if <VALUE> == <ARM>", so a mood only meets its own members
+        let mut comparison = frame.subject.clone();
+        comparison.push(Token::derived(constants::C_OP_EQ, &value));
+        comparison.push(value.clone());
+        expression::compile_condition(&comparison, symbols)?;
+
+        let fixed = declaration::fixed_value(std::slice::from_ref(&value), symbols).ok_or(MommyLangError::ConstantNotFixed)?;
+        let key = case_key(&fixed, symbols).ok_or(MommyLangError::TypeMismatch)?; // "when 2.5"
+
+        if frame.seen.contains(&key) {
+            return Err(MommyLangError::DuplicateArm);
+        }
+        frame.seen.push(key);
+        labels.push(format!("{} {}:", constants::C_KW_CASE, fixed));
+    }
+
+    Ok(format!("{}{} {{", close_arm(frame), labels.join(" ")))
+}
+
+pub fn otherwise(frame: &mut PickFrame) -> String {
+    format!("{}{}: {{", close_arm(frame), constants::C_KW_DEFAULT)
+}
+
+/// "done" closes the last arm and the pick itself.
+pub fn end_pick(frame: PickFrame) -> String {
+    match frame.has_arm {
+        true => format!("{} {}", constants::C_BREAK_BLOCK, constants::C_MAIN_END),
+        false => constants::C_MAIN_END.to_string(),
+    }
+}
+
+// ================================================================
+// HELPERS
+// ================================================================
+
+/// The arm before this one stops here, C would fall straight into the next one otherwise.
+fn close_arm(frame: &mut PickFrame) -> String {
+    if std::mem::replace(&mut frame.has_arm, true) {
+        return format!("{} ", constants::C_BREAK_BLOCK);
+    }
+    String::new()
+}
+
+/// One spelling per value, so "65" and "'A'" are caught as the same arm. None when C cannot use it as a case.
+fn case_key(fixed: &str, symbols: &SymbolTable) -> Option<String> {
+    if let Ok(number) = fixed.parse::<i64>() {
+        return Some(number.to_string());
+    }
+    if symbols.get(fixed).is_some_and(|symbol| symbol.is_choice()) { // A choice member stands for itself
+        return Some(fixed.to_string());
+    }
+
+    let letter = syntax_lexer::unescape(fixed.strip_prefix('\'')?.strip_suffix('\'')?); // '\n' is 10, like C says
+    let mut chars = letter.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some((c as u32).to_string()),
+        _ => Some(letter),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{declared, tokens};
+
+    fn picked(symbols: &SymbolTable) -> PickFrame {
+        start_pick(&tokens("pick letter"), symbols).unwrap().1
+    }
+
+    #[test]
+    fn arms_become_cases_that_stop_on_their_own() {
+        let symbols = declared(&["mayihave 65 in letter as int"]);
+        let (c_code, mut frame) = start_pick(&tokens("pick letter"), &symbols).unwrap();
+        assert_eq!(c_code, "switch (letter) {");
+        assert_eq!(when(&tokens("when 1"), &mut frame, &symbols).unwrap(), "case 1: {");
+        assert_eq!(when(&tokens("when 2, 3"), &mut frame, &symbols).unwrap(), "break; } case 2: case 3: {");
+        assert_eq!(otherwise(&mut frame), "break; } default: {");
+        assert_eq!(end_pick(frame), "break; } }");
+    }
+
+    #[test]
+    fn the_same_value_gets_one_arm() {
+        let symbols = declared(&["mayihave 65 in letter as int", "mayihave 2 in other as int"]);
+        let mut frame = picked(&symbols);
+        when(&tokens("when 65"), &mut frame, &symbols).unwrap();
+        assert_eq!(when(&tokens("when 'A'"), &mut frame, &symbols), Err(MommyLangError::DuplicateArm));
+        assert_eq!(when(&tokens("when 1, 1"), &mut frame, &symbols), Err(MommyLangError::DuplicateArm));
+        when(&tokens("when 10"), &mut frame, &symbols).unwrap();
+        assert_eq!(when(&tokens("when '\\n'"), &mut frame, &symbols), Err(MommyLangError::DuplicateArm));
+        when(&tokens("when '\\t'"), &mut frame, &symbols).unwrap();
+        assert_eq!(when(&tokens("when other"), &mut frame, &symbols), Err(MommyLangError::ConstantNotFixed));
+        assert_eq!(when(&tokens("when"), &mut frame, &symbols), Err(MommyLangError::MissingArguments));
+    }
+}
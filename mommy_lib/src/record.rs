@@ -0,0 +1,190 @@
+//!
+//!
+//! This is the record crate of mommylang.
+//!
+//! Language Syntax:
+//! - Definition: "record student has name as String, grade as float, id as int"
+//! - Declaration: "mayihave null in ann as student" (every field starts empty)
+//! - With values, in field order: "mayihave "Ann", 91.5, 7 in ann as student"
+//! - Copy: "mayihave ann in bob as student", "replace bob with ann"
+//! - Fields, anywhere a variable goes: "replace ann.grade with 95", "say ann.name", "ask if ann.grade > 90"
+//! - Groups and heap blocks: "group 30 in class as student", "replace class in 2.grade with 88",
+//!   "replace best with class in i.grade", "say class in 2.name"
+//! - Whole record: "say ann" and "say class in 2" print every field on one line
+//!
+//! Note:
+//! 1. Records become C structs ("typedef struct { ... } student;"), written above the chores and main.
+//! 2. Records are defined at the top level, so every chore can see them.
+//! 3. A field is an int, float, char, ascii, bool or String. A box or another record cannot be a field yet.
+//! 4. Every field of a record variable is its own symbol ("ann.grade"), so the rest of the compiler
+//!    treats it like any other variable.
+//!
+//! Symbol: kind Record, data_type is the record name, fields are the (name, type) pairs.
+//! A variable, group or heap block of a record type carries the same fields.
+//!
+//!
+use crate::constants;
+use crate::declaration;
+use crate::functions;
+use crate::lang_enums::SymbolKind;
+use crate::responses::MommyLangError;
+use crate::symbol_table::{Symbol, SymbolTable};
+use crate::validate_syntax;
+use crate::syntax_lexer::Token;
+
+// ================================================================
+// PUBLIC FUNCTIONS (The Logic)
+// ================================================================
+
+/// Registers the record and returns its C struct. The compiler puts it above main.
+pub fn define(
+    tokens: &[Token],
+    symbols: &mut SymbolTable
+) -> Result<String, MommyLangError> {
+    // Syntax: record <NAME> has <FIELD> as <TYPE>, ...
+
+    if tokens.len() <= constants::IDX_RECORD_KEY_HAS + 1 {
+        return Err(MommyLangError::MissingArguments);
+    }
+    if !tokens[constants::IDX_RECORD_KEY_HAS].is_keyword(constants::KW_HAS) {
+        return Err(MommyLangError::SyntaxError);
+    }
+
+    let name = tokens[constants::IDX_RECORD_NAME].as_str();
+    validate_syntax::ensure_valid_name(name)?;
+    validate_syntax::ensure_var_new(name, symbols)?;
+    if validate_syntax::is_builtin_type(name) { // "record String has ..." would replace a real type
+        return Err(MommyLangError::InvalidVariableName);
+    }
+
+    let fields = functions::parse_params(&tokens[constants::IDX_RECORD_KEY_HAS + 1..])?;
+
+    let mut c_fields = Vec::new();
+    for (index, (field, field_type)) in fields.iter().enumerate() {
+        validate_syntax::ensure_valid_name(field)?;
+        if fields[..index].iter().any(|(other, _)| other == field) {
+            return Err(MommyLangError::VariableAlreadyExists);
+        }
+        if field_type == constants::KW_POINTER {
+            return Err(MommyLangError::TypeMismatch);
+        }
+        c_fields.push(format!("{} {};", declaration::get_c_type(field_type), field));
+    }
+
+    symbols.insert(name, Symbol::record(name, fields));
+    Ok(format!("typedef struct {{ {} }} {};", c_fields.join(" "), name))
+}
+
+/// "mayihave <VALUES> in <NAME> as <RECORD>". The values are already type checked.
+pub fn declare(
+    name: &str,
+    record_type: &str,
+    value_tokens: &[Token],
+    symbols: &mut SymbolTable
+) -> Result<String, MommyLangError> {
+    let fields = symbols.lookup(record_type)?.fields.clone();
+    let values = declaration::split_initializer(value_tokens);
+
+    let value = match values.as_slice() {
+        [value] if value.is_keyword(constants::KW_NULL) => "{0}".to_string(),
+        [value] if symbols.get(value.as_str()).is_some_and(|symbol| symbol.is_record()) => value.to_string(), // A copy
+        _ => {
+            if values.len() > fields.len() {
+                return Err(MommyLangError::InitializerTooLong);
+            }
+            let mut c_values = Vec::new();
+            for value in &values {
+                if value.text.is_empty() { // "1, , 2"
+                    return Err(MommyLangError::SyntaxError);
+                }
+                c_values.push(declaration::c_value(std::slice::from_ref(value), symbols)?);
+            }
+            format!("{{{}}}", c_values.join(", "))
+        }
+    };
+
+    symbols.insert(name, Symbol { fields: fields.clone(), ..Symbol::scalar(record_type) });
+    for (field, field_type) in &fields { // "ann.grade" is a float like any other
+        declaration::insert_symbol(field_type, &format!("{}{}{}", name, constants::SYM_DOT, field), symbols);
+    }
+
+    Ok(format!("{} {} = {};", record_type, name, value))
+}
+
+/// A group or heap block of a record type carries the record's fields. Anything else is left alone.
+pub fn adopt(name: &str, raw_type: &str, symbols: &mut SymbolTable) {
+    let Some(fields) = record_fields(raw_type, symbols) else { return };
+    if let Some(symbol) = symbols.get_mut(name) {
+        symbol.fields = fields;
+    }
+}
+
+pub fn is_record_type(raw_type: &str, symbols: &SymbolTable) -> bool {
+    record_fields(raw_type, symbols).is_some()
+}
+
+/// "2.grade" is the slot "2" and its field "grade". A plain index has no field.
+pub fn split_field(index: &str) -> (&str, Option<&str>) {
+    match index.split_once(constants::SYM_DOT) {
+        Some((index, field)) => (index, Some(field)),
+        None => (index, None),
+    }
+}
+
+/// A slot with its field glued on, "class[2].grade".
+pub fn c_field(element: String, symbol: &Symbol, field: Option<&str>) -> Result<String, MommyLangError> {
+    let Some(field) = field else { return Ok(element) };
+    if symbol.field_type(field).is_none() {
+        return Err(MommyLangError::UnknownField);
+    }
+    Ok(format!("{}{}{}", element, constants::SYM_DOT, field))
+}
+
+// ================================================================
+// HELPERS
+// ================================================================
+
+fn record_fields(raw_type: &str, symbols: &SymbolTable) -> Option<Vec<(String, String)>> {
+    symbols.get(raw_type)
+        .filter(|symbol| symbol.kind == SymbolKind::Record)
+        .map(|symbol| symbol.fields.clone())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{declared, run};
+
+    const STUDENT: &str = "record student has name as String, grade as float, id as int";
+
+    #[test]
+    fn a_record_is_a_c_struct() {
+        let mut symbols = SymbolTable::new();
+        assert_eq!(run(STUDENT, &mut symbols).unwrap(), "typedef struct { char* name; float grade; int id; } student;");
+        assert_eq!(run("record pair has a as int, a as int", &mut symbols), Err(MommyLangError::VariableAlreadyExists));
+        assert_eq!(run("record String has a as int", &mut symbols), Err(MommyLangError::InvalidVariableName));
+        assert_eq!(run("record holder has p as box", &mut symbols), Err(MommyLangError::TypeMismatch));
+        assert_eq!(run("record empty", &mut symbols), Err(MommyLangError::MissingArguments));
+    }
+
+    #[test]
+    fn fields_work_like_variables() {
+        let mut symbols = declared(&[STUDENT]);
+        assert_eq!(run("mayihave \"Ann\", 91.5, 7 in ann as student", &mut symbols).unwrap(), "student ann = {\"Ann\", 91.5, 7};");
+        assert_eq!(run("mayihave null in bob as student", &mut symbols).unwrap(), "student bob = {0};");
+        assert_eq!(run("mayihave ann in cat as student", &mut symbols).unwrap(), "student cat = ann;");
+        assert_eq!(run("mayihave \"Dan\", 1.5, 2, 3 in dan as student", &mut symbols), Err(MommyLangError::InitializerTooLong));
+        assert_eq!(run("replace ann.grade with 95", &mut symbols).unwrap(), "ann.grade = 95;");
+        assert!(run("replace ann.id with \"seven\"", &mut symbols).is_err());
+    }
+
+    #[test]
+    fn groups_of_records_reach_into_their_slots() {
+        let mut symbols = declared(&[STUDENT, "mayihave 0.0 in best as float"]);
+        run("group 30 in class as student", &mut symbols).unwrap();
+        assert_eq!(run("replace class in 2.grade with 88", &mut symbols).unwrap(), "class[2].grade = 88;");
+        assert_eq!(run("replace best with class in 2.grade", &mut symbols).unwrap(), "best = class[2].grade;");
+        assert_eq!(run("replace class in 2.age with 1", &mut symbols), Err(MommyLangError::UnknownField));
+    }
+}
@@ -19,25 +19,54 @@ use std::fmt;
 // Usage: Parsing, Transpiling, Compiling, Logic, Math
 // =========================================================
 
+#[derive(Debug, PartialEq)]
 pub enum MommyLangError {
     // Syntax & Logic
     MissingArguments,
     InvalidVariableName,
     UndeclaredVariable,
+    OutOfScopeVariable,
+    ImmutableVariable,
     TypeMismatch,
+    TypeMismatchOn(String, String), // the two operands that do not fit
     SyntaxError,
+    UnclosedQuote,
+    UnknownEscape,
     UnclosedBlock,
     UnexpectedDone,
     VariableAlreadyExists,
     UnexpectedSatisfied,
+    UnexpectedSkip,
+    UnknownLoop,
+    UnexpectedAgain,
+    MissingAgain,
+    InvalidLoopStep,
     OrphanElse,
+    ElseAfterElse,
+    ArmOutsidePick,
+    ArmAfterOtherwise,
+    DuplicateArm,
+    OutsideArm,
     UnknownPackage,
+    UndeclaredFunction,
+    WrongArgumentCount,
+    NestedFunction,
+    UnexpectedGiveBack,
+    LeaveInsideFunction,
+    NestedType,
+    ConstantNotFixed,
+    UnknownField,
    
     // Array / Memory Specific (NEW)
     NotAnArray,         // Trying to use "in" on a normal variable
     IndexOutOfBounds,   // Accessing index 10 of size 5
     InvalidArraySize,   // "group -5" or "group hello"
+    InitializerTooLong, // "group 2 ... with 1, 2, 3"
+    WrongIndexCount,    // "board in 1,2,3" on a "group 3,4"
     AccessViolation,    // General memory safety
+    NotHeapMemory,      // "takeitback"/"ibegyoumore" on something "ibegyou" never gave
+    DoubleFree,         // "takeitback" twice
+    UseAfterFree,       // touching a block after "takeitback"
 
     // File / System
     StatusNoFile,
@@ -52,12 +81,16 @@ pub enum MommyLangError {
     RunFile,
     ExecutableFile,
     CannotCreateCFile,
+    InvalidErrorLimit,
+    TooManyErrors(usize), // the limit that was reached
     
 
     // Math (ALU)
     MathOnString,
     DivideByZero,
     NotANumber,
+    UnbalancedParentheses,
+    MalformedOperator,
     
     // UI
     ErrorBegins,
@@ -65,6 +98,13 @@ pub enum MommyLangError {
 
 }
 
+// Not fatal, the program still compiles. The name is the variable it is about.
+pub enum MommyLangWarning {
+    HeapLeak(String),      // "ibegyou" without "takeitback"
+    MaybeReleased(String), // given back in a block that might have run
+    CounterRenamed(String, String), // an anonymous "punishme" whose counter name was taken: yours, and the one it got
+}
+
 pub enum MommyLangStatus {
     ReadingFile,
     RenameFile,
@@ -172,26 +212,56 @@ impl fmt::Display for MommyLangError {
             // LOGIC ERRORS
             Self::MissingArguments => write!(f, "You stopped talking mid-sentence. Do not tease me. Finish what you started."),
             Self::UndeclaredVariable => write!(f, "Who is that? You are making up names again. Define them first or stay silent."),
+            Self::OutOfScopeVariable => write!(f, "That one stayed behind in its block. You left it there, you cannot reach it from here."),
+            Self::ImmutableVariable => write!(f, "That one is not yours to change. Hands off."),
             Self::InvalidVariableName => write!(f, "That name is forbidden. Do not test my authority."),
             Self::TypeMismatch => write!(f, "Square peg, round hole. Stop forcing things where they don't belong."),
+            Self::TypeMismatchOn(target, value) => write!(f, "Square peg, round hole. {} and {} do not belong together.", target, value),
             Self::SyntaxError => write!(f, "I can't read this mess. Fix your grammar before I lose my patience."),
+            Self::UnclosedQuote => write!(f, "You opened a quote and never closed it. Finish your sentence, sweetie."),
+            Self::UnknownEscape => write!(f, "I don't know that backslash. Use \\n, \\t, \\r, \\0, \\\\, \\\" or \\' like a good child."),
             Self::UnclosedBlock => write!(f, "You opened a door and forgot to close it. Were you raised in a barn? Close your blocks."),
             Self::UnexpectedDone => write!(f, "You said 'done' but you haven't even started. Focus."),
             Self::VariableAlreadyExists => write!(f, "We already have that. Be creative, or be quiet."),
             Self::UnexpectedSatisfied => write!(f, "You skipped the work but want the reward? Pathetic."),
+            Self::UnexpectedSkip => write!(f, "Skip to what? You are not even being punished right now."),
+            Self::UnknownLoop => write!(f, "There is no loop by that name around you. Do not make up punishments you are not in."),
+            Self::UnexpectedAgain => write!(f, "Again? You never started a 'punishmefirst'. Only that one ends with 'againif'."),
+            Self::MissingAgain => write!(f, "A 'punishmefirst' does not end with 'done'. Tell me when to go again with 'againif'."),
+            Self::InvalidLoopStep => write!(f, "A step of zero? You want to be punished forever? Move, or don't start."),
             Self::OrphanElse => write!(f, "This 'else' has no 'if'. It is alone, just like you will be if you keep this up."),
+            Self::ElseAfterElse => write!(f, "You already said 'or'. That was your last choice, there is nothing after the last choice."),
+            Self::ArmOutsidePick => write!(f, "'when' what? You are not picking anything right now."),
+            Self::ArmAfterOtherwise => write!(f, "You already said 'otherwise'. Everything else went there, there is nothing left to pick."),
+            Self::DuplicateArm => write!(f, "You already told me what to do with that one. Make up your mind."),
+            Self::OutsideArm => write!(f, "Which 'when' does this belong to? Pick an arm first, I am not guessing."),
             Self::UnknownPackage => write!(f, "We do not have that kind of tool in our house."),
+            Self::UndeclaredFunction => write!(f, "I never taught you that chore. Explain it to me first, then ask me to do it."),
+            Self::WrongArgumentCount => write!(f, "That is not what this chore needs. Count what you are handing me, sweetie."),
+            Self::NestedFunction => write!(f, "One chore at a time. Finish the one you are in before you start making up new ones."),
+            Self::UnexpectedGiveBack => write!(f, "Give back what? You are not even doing a chore right now."),
+            Self::NestedType => write!(f, "Records and choices are decided up front, not in the middle of something else. Put it at the top level."),
+            Self::ConstantNotFixed => write!(f, "A promise is made up front. Give me a value I know right now, not one you will work out later."),
+            Self::UnknownField => write!(f, "That record has no such pocket. Stop inventing fields it does not have."),
+            Self::LeaveInsideFunction => write!(f, "You do not get to leave in the middle of a chore. Give it back and finish properly."),
 
             // MEMORY / ARRAYS (The New Stuff)
             Self::NotAnArray => write!(f, "That is just one thing, not a group. You cannot reach inside it."),
             Self::IndexOutOfBounds => write!(f, "You are reaching too far! That shelf doesn't exist. Keep your hands where I can see them."),
             Self::InvalidArraySize => write!(f, "A group cannot be that size. Use a real number, don't be stupid."),
+            Self::WrongIndexCount => write!(f, "Count the sides of that group again. One number per side, no more, no less."),
+            Self::InitializerTooLong => write!(f, "You are stuffing more into that group than you made room for. Count first, then pack."),
             Self::AccessViolation => write!(f, "Do not touch that memory. That is MINE."),
+            Self::NotHeapMemory => write!(f, "You never begged for that one. It is not a heap block, so stop treating it like one."),
+            Self::DoubleFree => write!(f, "You already gave that back. Giving it back twice does not make you twice as good."),
+            Self::UseAfterFree => write!(f, "You gave that back already. It is not yours anymore, stop touching it."),
 
             // MATH ERRORS
             Self::MathOnString => write!(f, "You cannot do math on words. Stop acting childish."),
             Self::DivideByZero => write!(f, "Divide by zero? Do you WANT to break the universe? Don't be stupid."),
             Self::NotANumber => write!(f, "That is not a number. Are you trying to trick me with words? Don't waste my time."),
+            Self::MalformedOperator => write!(f, "What kind of operator is that? Say '==' or 'and' properly, I will not guess what you meant."),
+            Self::UnbalancedParentheses => write!(f, "You opened a bracket and never closed it. Finish your thoughts before you speak to me."),
 
             // SYSTEM ERRORS
             Self::StatusNoFile => write!(f, "There is nothing here. Stop wasting my time."),
@@ -206,6 +276,8 @@ impl fmt::Display for MommyLangError {
             Self::RunFile => write!(f, "Mommy is disappointed. Program exited with code."),
             Self::ExecutableFile => write!(f, "Could not start the executable."),
             Self::CannotCreateCFile => write!(f, "We cannot convert this file"),
+            Self::InvalidErrorLimit => write!(f, "Stop after how many? Put a real number after --max-errors, at least one."),
+            Self::TooManyErrors(limit) => write!(f, "That is enough. I stopped reading after {} mistakes. Fix these first, then come back.", limit),
           
 
             Self::ErrorBegins => write!(f, "--- MOMMY IS DISAPPOINTED ---"),
@@ -214,6 +286,16 @@ impl fmt::Display for MommyLangError {
     }
 }
 
+impl fmt::Display for MommyLangWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        match self{
+            Self::HeapLeak(name) => write!(f, "You begged for '{}' and never gave it back. Mommy noticed.", name),
+            Self::MaybeReleased(name) => write!(f, "'{}' might already be given back by now. Are you sure it is still yours?", name),
+            Self::CounterRenamed(name, counter) => write!(f, "You already have an '{}', so this loop counts with '{}'. Your '{}' is not the counter in there.", name, counter, name),
+        }
+    }
+}
+
 impl fmt::Display for MommyLangStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
         match self{
@@ -325,4 +407,143 @@ impl fmt::Display for MommyUI {
             Self::NewLine => write!(f, "\n\n\n"),
         }
     }
-}
\ No newline at end of file
+}
+// =========================================================
+// 4. THE RECEIPT (DIAGNOSTIC CODES & HELP)
+// Usage: diagnostic.rs, so every complaint can be looked up and fixed
+// =========================================================
+
+impl MommyLangError {
+    /// A short code that never changes, even when the wording does.
+    pub fn code(&self) -> &'static str {
+        match self {
+            // LOGIC ERRORS
+            Self::MissingArguments => "E001",
+            Self::InvalidVariableName => "E002",
+            Self::UndeclaredVariable => "E003",
+            Self::OutOfScopeVariable => "E004",
+            Self::ImmutableVariable => "E005",
+            Self::TypeMismatch | Self::TypeMismatchOn(_, _) => "E006",
+            Self::SyntaxError => "E007",
+            Self::UnclosedQuote => "E008",
+            Self::UnknownEscape => "E009",
+            Self::UnclosedBlock => "E010",
+            Self::UnexpectedDone => "E011",
+            Self::VariableAlreadyExists => "E012",
+            Self::UnexpectedSatisfied => "E013",
+            Self::UnexpectedSkip => "E014",
+            Self::UnknownLoop => "E015",
+            Self::UnexpectedAgain => "E016",
+            Self::MissingAgain => "E017",
+            Self::InvalidLoopStep => "E018",
+            Self::OrphanElse => "E019",
+            Self::ElseAfterElse => "E020",
+            Self::ArmOutsidePick => "E021",
+            Self::ArmAfterOtherwise => "E022",
+            Self::DuplicateArm => "E023",
+            Self::OutsideArm => "E024",
+            Self::UnknownPackage => "E025",
+            Self::UndeclaredFunction => "E026",
+            Self::WrongArgumentCount => "E027",
+            Self::NestedFunction => "E028",
+            Self::UnexpectedGiveBack => "E029",
+            Self::LeaveInsideFunction => "E030",
+            Self::NestedType => "E031",
+            Self::ConstantNotFixed => "E032",
+            Self::UnknownField => "E033",
+
+            // MEMORY / ARRAYS
+            Self::NotAnArray => "E101",
+            Self::IndexOutOfBounds => "E102",
+            Self::InvalidArraySize => "E103",
+            Self::InitializerTooLong => "E104",
+            Self::WrongIndexCount => "E105",
+            Self::AccessViolation => "E106",
+            Self::NotHeapMemory => "E107",
+            Self::DoubleFree => "E108",
+            Self::UseAfterFree => "E109",
+
+            // MATH ERRORS
+            Self::MathOnString => "E201",
+            Self::DivideByZero => "E202",
+            Self::NotANumber => "E203",
+            Self::UnbalancedParentheses => "E204",
+            Self::MalformedOperator => "E205",
+
+            // SYSTEM ERRORS
+            Self::StatusNoFile => "E301",
+            Self::WrongFileType => "E302",
+            Self::ConfigCreationError => "E303",
+            Self::ConvertLangFailed => "E304",
+            Self::TranspilingError => "E305",
+            Self::RuntimeError => "E306",
+            Self::GCCError => "E307",
+            Self::CannotReadFile => "E308",
+            Self::GCCNotFound => "E309",
+            Self::RunFile => "E310",
+            Self::ExecutableFile => "E311",
+            Self::CannotCreateCFile => "E312",
+            Self::InvalidErrorLimit => "E313",
+            Self::TooManyErrors(_) => "E314",
+
+            Self::ErrorBegins | Self::ErrorEnds => "E000",
+        }
+    }
+
+    /// What to do about it, for the errors where the fix is not obvious from the scolding.
+    pub fn help(&self) -> Option<&'static str> {
+        match self {
+            Self::UndeclaredVariable => Some("declare it first: mayihave <VALUE> in <NAME> as <TYPE>"),
+            Self::OutOfScopeVariable => Some("declare it before the block opens if you still need it after 'done'"),
+            Self::ImmutableVariable => Some("'always' values and choice members never change, use 'mayihave' for one that does"),
+            Self::TypeMismatch | Self::TypeMismatchOn(_, _) => Some("'turn <VALUE> into <TARGET>' converts between types"),
+            Self::UnclosedQuote => Some("a text closes with \" and a char closes with '"),
+            Self::UnknownEscape => Some("write \\\\ for a backslash that stays a backslash"),
+            Self::UnclosedBlock => Some("every ask, pick, punishme, punishmeif and chore ends with 'done', a punishmefirst with 'againif'"),
+            Self::UnexpectedDone => Some("every 'done' closes one block that is still open"),
+            Self::UnexpectedSatisfied | Self::UnexpectedSkip => Some("'satisfied' and 'skip' only work inside punishme, punishmeif or punishmefirst"),
+            Self::UnknownLoop => Some("name a loop by ending its header with 'named <LABEL>'"),
+            Self::UnexpectedAgain => Some("only 'punishmefirst' ends with 'againif', every other loop ends with 'done'"),
+            Self::MissingAgain => Some("end a punishmefirst with 'againif <CONDITION>'"),
+            Self::InvalidLoopStep => Some("the counter is an int, so step by a whole number that is not 0"),
+            Self::OrphanElse => Some("'or' and 'or ask if' go right after an 'ask' block"),
+            Self::ArmOutsidePick => Some("'when' and 'otherwise' go right inside a 'pick'"),
+            Self::OutsideArm => Some("start with 'when <VALUE>' right after 'pick'"),
+            Self::UndeclaredFunction => Some("define it first: chore <NAME> needs <PARAM> as <TYPE> gives <TYPE>"),
+            Self::NestedFunction => Some("close the chore you are in with 'done' first"),
+            Self::NestedType => Some("put 'record' and 'choices' at the top level, outside every block"),
+            Self::ConstantNotFixed => Some("use a literal, another 'always' value or a choice member"),
+            Self::NotAnArray => Some("'in' only reaches into a group, a String or a heap block"),
+            Self::IndexOutOfBounds => Some("slots start at 0 and stop one before the size"),
+            Self::DoubleFree | Self::UseAfterFree => Some("a block is gone after 'takeitback', beg for a new one with 'ibegyou'"),
+            Self::WrongFileType => Some("the file has to end in .mommy"),
+            Self::TooManyErrors(_) => Some("'--max-errors <N>' changes how many mistakes are reported in one run"),
+            _ => None,
+        }
+    }
+}
+
+impl MommyLangWarning {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::HeapLeak(_) => "W001",
+            Self::MaybeReleased(_) => "W002",
+            Self::CounterRenamed(..) => "W003",
+        }
+    }
+
+    pub fn help(&self) -> Option<&'static str> {
+        match self {
+            Self::HeapLeak(_) => Some("give it back with 'takeitback <NAME>' once you are done with it"),
+            Self::MaybeReleased(_) => None,
+            Self::CounterRenamed(..) => Some("name the counter yourself: 'punishme <COUNT> as <NAME>'"),
+        }
+    }
+
+    /// The variable the warning is about.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::HeapLeak(name) | Self::MaybeReleased(name) | Self::CounterRenamed(name, _) => name,
+        }
+    }
+}
@@ -26,7 +26,7 @@ pub enum MommyShellCommands {
 
 
 impl MommyShellCommands {
-    pub fn from_str(token: &str) -> Self {
+    pub fn from_word(token: &str) -> Self {
         match token {
             "tellme" => MommyShellCommands::ShellHelp,
             "tellmesecret" => MommyShellCommands::ShellHelpAdvanced,
@@ -0,0 +1,476 @@
+//!
+//!
+//! This is the symbol table crate of mommylang.
+//!
+//! Every block ("punishme", "punishmeif", "ask", "or") gets its own scope, pushed and popped
+//! together with the compiler's scope stack. That gives us the same rules as the C we emit:
+//!
+//! 1. A variable declared inside a block is gone after "done".
+//! 2. Sibling blocks can reuse the same names.
+//! 3. An inner block can shadow an outer name, the innermost one wins.
+//! 4. Using a variable after its block closed is reported as out of scope, not as a made-up name.
+//! 5. A heap block still live when its block closes is a leak. One given back inside a block that closed
+//!    might not have been given back at all. Sibling "or" branches do not count against each other.
+//!
+//! Every name carries a `Symbol`: what kind of thing it is, its element type, its size and the line
+//! that declared it. Nothing is re-split from strings anymore.
+//!
+//! The table also knows the source line being compiled and which runtime checks are on, since every
+//! statement that needs them already gets the table. Warnings are collected here too, by line.
+//!
+//! After a transpile, `declarations()` lists every symbol that was ever declared (chores and closed
+//! blocks included), in source order and as it was last seen: a resized or given back heap block
+//! shows that, not how it was begged for. Tools can inspect the program with it. The table comes back from
+//! `mommy_lang::compiler::transpile_code_to_c`.
+//!
+//!
+use std::collections::{HashMap, HashSet};
+use crate::constants;
+use crate::lang_enums::{Lifetime, SymbolKind};
+use crate::responses::{MommyLangError, MommyLangWarning};
+use crate::text;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    pub data_type: String,     // Element type for arrays and heap, return type for chores
+    pub size: Option<String>,  // Arrays and heap only, as written ("5" or "n"). The first side of a grid
+    pub dims: Vec<usize>,      // Grids only, every side ("group 3,4" is [3, 4])
+    pub params: Vec<String>,   // Chores only
+    pub fields: Vec<(String, String)>, // Records, and everything of a record type: (name, type) per field
+    pub members: Vec<String>,  // Choices, and everything of a choice type: the member names in order
+    pub constant: Option<String>, // "always" and choice members: the value as C sees it, known before the program runs
+    pub line: usize,           // 0 if declared outside of a source line
+    pub mutable: bool,
+    pub lifetime: Lifetime,    // Heap only, see the lifetime crate
+}
+
+impl Symbol {
+    pub fn scalar(data_type: &str) -> Self {
+        Self::new(SymbolKind::Scalar, data_type, None)
+    }
+
+    pub fn pointer() -> Self {
+        Self::new(SymbolKind::Pointer, constants::TYPE_INT, None) // "box" is always an int*
+    }
+
+    pub fn array(data_type: &str, size: &str) -> Self {
+        Self::new(SymbolKind::Array, data_type, Some(size.to_string()))
+    }
+
+    /// A group with two or more sides, "group 3,4 in board as int".
+    pub fn grid(data_type: &str, dims: Vec<usize>) -> Self {
+        let first = dims.first().map(|side| side.to_string()).unwrap_or_default();
+        Symbol {
+            dims,
+            ..Self::new(SymbolKind::Array, data_type, Some(first))
+        }
+    }
+
+    pub fn heap(data_type: &str, size: &str) -> Self {
+        Self::new(SymbolKind::Heap, data_type, Some(size.to_string()))
+    }
+
+    pub fn function(return_type: &str, params: Vec<String>) -> Self {
+        Symbol {
+            params,
+            mutable: false, // "replace fib with 3" makes no sense
+            ..Self::new(SymbolKind::Function, return_type, None)
+        }
+    }
+
+    /// "record student has name as String, grade as float"
+    pub fn record(name: &str, fields: Vec<(String, String)>) -> Self {
+        Symbol {
+            fields,
+            mutable: false,
+            ..Self::new(SymbolKind::Record, name, None)
+        }
+    }
+
+    /// "choices mood has happy, tired, angry"
+    pub fn choice(name: &str, members: Vec<String>) -> Self {
+        Symbol {
+            members,
+            mutable: false,
+            ..Self::new(SymbolKind::Choice, name, None)
+        }
+    }
+
+    fn new(kind: SymbolKind, data_type: &str, size: Option<String>) -> Self {
+        Symbol {
+            kind,
+            data_type: data_type.to_string(),
+            size,
+            dims: Vec::new(),
+            params: Vec::new(),
+            fields: Vec::new(),
+            members: Vec::new(),
+            constant: None,
+            line: 0,
+            mutable: true,
+            lifetime: Lifetime::Live,
+        }
+    }
+
+    /// Arrays and heap blocks can be used with "in <INDEX>".
+    pub fn is_indexable(&self) -> bool {
+        matches!(self.kind, SymbolKind::Array | SymbolKind::Heap)
+    }
+
+    pub fn is_grid(&self) -> bool {
+        self.dims.len() > 1
+    }
+
+    /// The size, if it is a number we know at compile time ("ibegyou n" is not).
+    pub fn fixed_size(&self) -> Option<usize> {
+        self.size.as_ref().and_then(|size| size.parse::<usize>().ok())
+    }
+
+    /// A variable, group or heap block whose type is a record. The record itself is not one.
+    pub fn is_record(&self) -> bool {
+        self.kind != SymbolKind::Record && !self.fields.is_empty()
+    }
+
+    /// A member, variable, group or heap block whose type is a choice. The choice itself is not one.
+    pub fn is_choice(&self) -> bool {
+        self.kind != SymbolKind::Choice && !self.members.is_empty()
+    }
+
+    pub fn field_type(&self, field: &str) -> Option<&str> {
+        self.fields.iter().find(|(name, _)| name == field).map(|(_, field_type)| field_type.as_str())
+    }
+
+    pub fn is_text(&self) -> bool {
+        matches!(self.data_type.as_str(), constants::TYPE_STRING | constants::C_TYPE_CHAR_PTR | constants::TYPE_TEXT)
+    }
+}
+
+pub struct SymbolTable {
+    scopes: Vec<HashMap<String, Symbol>>,
+    retired: HashSet<String>, // names that died with their block
+    history: Vec<(String, Symbol)>,
+    slots: Vec<HashMap<String, usize>>, // where each name of the open rooms sits in the history
+    line: usize,
+    bounds_check: bool,
+    track_heap: bool,
+    warnings: Vec<(usize, MommyLangWarning)>,
+    branch_released: Vec<(usize, String)>, // given back in an earlier "or" branch, at this depth
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        SymbolTable {
+            scopes: vec![HashMap::new()],
+            retired: HashSet::new(),
+            history: Vec::new(),
+            slots: vec![HashMap::new()],
+            line: 0,
+            bounds_check: false,
+            track_heap: false,
+            warnings: Vec::new(),
+            branch_released: Vec::new(),
+        }
+    }
+
+    /// A fresh table for a chore body: only chores, records, choices and constants are carried over.
+    pub fn for_chore(&self) -> Self {
+        let mut chore_symbols = SymbolTable::new();
+        chore_symbols.line = self.line;
+        chore_symbols.bounds_check = self.bounds_check;
+        chore_symbols.track_heap = self.track_heap;
+        for scope in &self.scopes {
+            // Types and constants are known everywhere, variables are not
+            let shared = scope.iter().filter(|(_, s)|
+                matches!(s.kind, SymbolKind::Function | SymbolKind::Record | SymbolKind::Choice) || s.constant.is_some());
+            for (name, symbol) in shared {
+                chore_symbols.scopes[0].insert(name.clone(), symbol.clone());
+            }
+        }
+        chore_symbols
+    }
+
+    /// Keeps the declarations and warnings of a finished chore, so they still show up in `declarations()`.
+    pub fn absorb_history(&mut self, chore_symbols: SymbolTable) {
+        self.history.extend(chore_symbols.declarations());
+        self.warnings.extend(chore_symbols.warnings);
+    }
+
+    /// The source line that new symbols are stamped with.
+    pub fn set_line(&mut self, line: usize) {
+        self.line = line;
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Variable indices get checked while the program runs.
+    pub fn set_bounds_check(&mut self, enabled: bool) {
+        self.bounds_check = enabled;
+    }
+
+    pub fn bounds_check(&self) -> bool {
+        self.bounds_check
+    }
+
+    /// Every "ibegyou" block is tracked while the program runs, and "leave" reports the ones left over.
+    pub fn set_track_heap(&mut self, enabled: bool) {
+        self.track_heap = enabled;
+    }
+
+    pub fn track_heap(&self) -> bool {
+        self.track_heap
+    }
+
+    pub fn warn(&mut self, warning: MommyLangWarning) {
+        self.warnings.push((self.line, warning));
+    }
+
+    /// Every warning so far, with the line it belongs to.
+    pub fn warnings(&self) -> &[(usize, MommyLangWarning)] {
+        &self.warnings
+    }
+
+    /// How many blocks deep we are. The outermost room is 1.
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+        self.slots.push(HashMap::new());
+    }
+
+    pub fn pop_scope(&mut self) {
+        if self.scopes.len() <= 1 { // The outermost room never closes
+            return;
+        }
+        let depth = self.scopes.len();
+        self.close_scope();
+
+        // Given back in here, or in an earlier branch of the same "ask": it might not have happened
+        let branch_released: Vec<String> = self.branch_released.iter()
+            .filter(|(at, _)| *at == depth)
+            .map(|(_, name)| name.clone())
+            .collect();
+        self.branch_released.retain(|(at, _)| *at != depth);
+
+        for scope in self.scopes.iter_mut() {
+            for (name, symbol) in scope.iter_mut() {
+                let released_inside = matches!(symbol.lifetime, Lifetime::Released(at) if at >= depth);
+                if released_inside || branch_released.contains(name) {
+                    symbol.lifetime = Lifetime::MaybeReleased;
+                }
+            }
+        }
+    }
+
+    /// "or": the next branch gets a fresh room, and does not see what the last branch gave back.
+    pub fn switch_branch(&mut self) {
+        if self.scopes.len() <= 1 {
+            return;
+        }
+        let depth = self.scopes.len();
+        self.close_scope();
+
+        for scope in self.scopes.iter_mut() {
+            for (name, symbol) in scope.iter_mut() {
+                if matches!(symbol.lifetime, Lifetime::Released(at) if at >= depth) {
+                    symbol.lifetime = Lifetime::Live;
+                    self.branch_released.push((depth, name.clone()));
+                }
+            }
+        }
+        self.push_scope();
+    }
+
+    /// The texts declared in the innermost room, which its closing brace has to free.
+    pub fn texts_in_scope(&self) -> Vec<String> {
+        self.texts_from(self.scopes.len())
+    }
+
+    /// The texts declared in room `depth` and every room inside it, oldest first.
+    /// A jump out of those rooms has to free them, they were all declared before it.
+    /// Room 1 is the whole program, which is never left, or the whole chore in a chore's own table.
+    pub fn texts_from(&self, depth: usize) -> Vec<String> {
+        let mut texts: Vec<(usize, String)> = self.scopes.iter()
+            .skip(depth.saturating_sub(1))
+            .flat_map(|scope| scope.iter())
+            .filter(|(_, symbol)| text::is_managed(symbol))
+            .map(|(name, symbol)| (symbol.line, name.clone()))
+            .collect();
+        texts.sort(); // HashMap order is random, the C should not be
+        texts.into_iter().map(|(_, name)| name).collect()
+    }
+
+    /// Warns about every heap block that is still live in the open rooms.
+    pub fn check_leaks(&mut self) {
+        let leaks: Vec<(usize, String)> = self.scopes.iter()
+            .flat_map(|scope| scope.iter())
+            .filter(|(_, symbol)| is_leaking(symbol))
+            .map(|(name, symbol)| (symbol.line, name.clone()))
+            .collect();
+        self.warn_leaks(leaks);
+    }
+
+    /// Innermost declaration wins.
+    pub fn get(&self, name: &str) -> Option<&Symbol> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    /// Like `get`, but tells the difference between a made-up name and one that went out of scope.
+    pub fn lookup(&self, name: &str) -> Result<&Symbol, MommyLangError> {
+        match self.get(name) {
+            Some(symbol) => Ok(symbol),
+            None if self.retired.contains(name) => Err(MommyLangError::OutOfScopeVariable),
+            // "ann.age" when ann is there but has no age
+            None if name.split_once(constants::SYM_DOT)
+                .is_some_and(|(owner, _)| self.get(owner).is_some_and(|symbol| symbol.is_record())) => Err(MommyLangError::UnknownField),
+            None => Err(MommyLangError::UndeclaredVariable),
+        }
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Symbol> {
+        self.scopes.iter_mut().rev().find_map(|scope| scope.get_mut(name))
+    }
+
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Only the current block counts, so sibling blocks can reuse names.
+    pub fn declared_here(&self, name: &str) -> bool {
+        self.scopes.last().is_some_and(|scope| scope.contains_key(name))
+    }
+
+    pub fn insert(&mut self, name: &str, mut symbol: Symbol) {
+        symbol.line = self.line;
+        if let Some(slots) = self.slots.last_mut() {
+            slots.insert(name.to_string(), self.history.len());
+        }
+        self.history.push((name.to_string(), symbol.clone()));
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), symbol);
+        }
+    }
+
+    /// Every symbol declared so far, in the order they were declared, including the ones out of scope.
+    /// Each one is in its latest state, so constants, resized and given back heap blocks show it.
+    pub fn declarations(&self) -> Vec<(String, Symbol)> {
+        let mut declarations = self.history.clone();
+        for (scope, slots) in self.scopes.iter().zip(&self.slots) {
+            for (name, &slot) in slots {
+                if let Some(symbol) = scope.get(name) {
+                    declarations[slot].1 = symbol.clone();
+                }
+            }
+        }
+        declarations
+    }
+
+    fn close_scope(&mut self) {
+        let slots = self.slots.pop().unwrap_or_default();
+        if let Some(scope) = self.scopes.pop() {
+            for (name, &slot) in &slots { // The room is gone, its history keeps how it ended
+                if let Some(symbol) = scope.get(name) {
+                    self.history[slot].1 = symbol.clone();
+                }
+            }
+            let leaks = scope.iter()
+                .filter(|(_, symbol)| is_leaking(symbol))
+                .map(|(name, symbol)| (symbol.line, name.clone()))
+                .collect();
+            self.warn_leaks(leaks);
+            self.retired.extend(scope.into_keys());
+        }
+    }
+
+    fn warn_leaks(&mut self, mut leaks: Vec<(usize, String)>) {
+        leaks.sort(); // HashMap order is random, warnings should not be
+        for (line, name) in leaks {
+            self.warnings.push((line, MommyLangWarning::HeapLeak(name)));
+        }
+    }
+}
+
+fn is_leaking(symbol: &Symbol) -> bool {
+    symbol.kind == SymbolKind::Heap && symbol.lifetime == Lifetime::Live
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::run;
+
+    fn type_of(symbols: &SymbolTable, name: &str) -> Option<String> {
+        symbols.get(name).map(|symbol| symbol.data_type.clone())
+    }
+
+    #[test]
+    fn block_names_are_gone_after_done() {
+        let mut symbols = SymbolTable::new();
+        symbols.push_scope();
+        symbols.insert("x", Symbol::scalar("int"));
+        assert!(symbols.contains_key("x"));
+        symbols.pop_scope();
+
+        assert_eq!(symbols.lookup("x"), Err(MommyLangError::OutOfScopeVariable));
+        assert_eq!(symbols.lookup("ghost"), Err(MommyLangError::UndeclaredVariable));
+    }
+
+    #[test]
+    fn sibling_blocks_reuse_names() {
+        let mut symbols = SymbolTable::new();
+        symbols.push_scope();
+        symbols.insert("x", Symbol::scalar("int"));
+        symbols.pop_scope();
+
+        symbols.push_scope();
+        assert!(!symbols.declared_here("x"));
+        symbols.insert("x", Symbol::scalar("float"));
+        assert_eq!(type_of(&symbols, "x").as_deref(), Some("float"));
+    }
+
+    #[test]
+    fn innermost_name_wins_until_its_block_ends() {
+        let mut symbols = SymbolTable::new();
+        symbols.insert("x", Symbol::scalar("int"));
+        symbols.push_scope();
+        symbols.insert("x", Symbol::scalar("float"));
+        assert_eq!(type_of(&symbols, "x").as_deref(), Some("float"));
+
+        symbols.pop_scope();
+        assert_eq!(type_of(&symbols, "x").as_deref(), Some("int"));
+        symbols.pop_scope(); // The outermost room never closes
+        assert_eq!(type_of(&symbols, "x").as_deref(), Some("int"));
+    }
+
+    #[test]
+    fn declarations_show_how_each_symbol_ended() {
+        let mut symbols = SymbolTable::new();
+        for line in ["always 3 in top as int", "ibegyou 2 in p as int", "ibegyoumore 5 in p"] {
+            run(line, &mut symbols).unwrap();
+        }
+        symbols.push_scope();
+        run("ibegyou 2 in q as int", &mut symbols).unwrap();
+        run("takeitback q", &mut symbols).unwrap();
+        symbols.pop_scope();
+        run("ibegyou 2 in r as int", &mut symbols).unwrap();
+        run("takeitback r", &mut symbols).unwrap();
+
+        let declarations = symbols.declarations();
+        let of = |name: &str| &declarations.iter().find(|(declared, _)| declared == name).unwrap().1;
+        assert!(!of("top").mutable);
+        assert_eq!(of("p").size.as_deref(), Some("_mommy_size_p")); // Not the 2 it was begged with
+        assert_eq!(of("q").lifetime, Lifetime::Released(2)); // Given back in its own room, before it closed
+        assert_eq!(of("r").lifetime, Lifetime::Released(1));
+    }
+}
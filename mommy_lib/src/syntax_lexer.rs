@@ -1,33 +1,460 @@
-// Insert Token
-pub fn insert_token(input: &str) -> Vec<String>{
+//!
+//!
+//! This is the lexer crate of mommylang.
+//!
+//! A line is split on whitespace into typed tokens, and every token remembers where it came from.
+//! - Keyword: "mayihave", "in", "yes"            - Identifier: "age", "ann.grade"
+//! - Number: "10", "-2.5"                         - Text: "\"hi\"" (the quotes stay on)
+//! - Char: "'a'", "'\n'"                          - Operator: "==", "+", "<="
+//! - Compound: pieces glued together without spaces, "fib(n)", "x+1", "2,3". `split` takes those apart.
+//! - Punctuation: "(", ")" and ",", on their own or out of a compound.
+//!
+//! Note:
+//! 1. Escapes inside texts and chars: \n \t \r \0 \\ \" \'. Anything else after a backslash is rejected,
+//!    so every text is already a valid C literal and goes into the C file as it was written.
+//! 2. "#" or the word "psst" starts a comment, unless it sits inside a text or a char.
+//! 3. Columns count characters from 1 on the line as it was written, leading spaces included.
+//!
+//!
+use std::fmt;
+
+use crate::constants;
+use crate::diagnostic::Diagnostic;
+use crate::lang_syntax::MommyLangSyntax;
+use crate::responses::MommyLangError;
+
+// Words that glue a statement together, on top of the ones that start one
+const CONNECTOR_WORDS: [&str; 25] = [
+    constants::KW_IN, constants::KW_AS, constants::KW_WITH, constants::KW_UPTO, constants::KW_INTO,
+    constants::KW_ONTO, constants::KW_NEEDS, constants::KW_GIVES, constants::KW_HAS, constants::KW_FROM,
+    constants::KW_TO, constants::KW_STEP, constants::KW_NAMED, constants::KW_IF, constants::KW_AND,
+    constants::KW_OR, constants::KW_NOT, constants::KW_YES, constants::KW_NO, constants::KW_TRUE,
+    constants::KW_FALSE, constants::KW_NULL, constants::KW_ADDR, constants::KW_DEREF, constants::KW_ALL,
+];
+
+const ESCAPES: [char; 7] = ['n', 't', 'r', '0', '\\', '"', '\''];
+const OPERATOR_CHARS: &str = "=!<>+-*/%&|";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TokenKind {
+    Keyword,
+    Identifier,
+    Number,
+    Text,     // "hello", quotes and escapes as written
+    Char,     // 'a'
+    Operator,
+    Punctuation, // "(", ")", ","
+    Compound, // "fib(n)", "x+1", several pieces with no space between them
+}
+
+/// Where a token sits in the source. Line and column start at 1, length is in characters.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub span: Span,
+}
+
+/// One line of source: its tokens, and the comment after them if it had one.
+pub struct Line {
+    pub tokens: Vec<Token>,
+    pub comment: Option<String>,
+}
+
+impl Token {
+    pub fn new(text: &str, span: Span) -> Self {
+        Token { kind: classify(text), text: text.to_string(), span }
+    }
+
+    /// A token made out of another one, like a value cut from "1, 2, 3" or a call put back together.
+    /// It points at the same place as the token it came from.
+    pub fn derived(text: &str, from: &Token) -> Self {
+        Token::new(text, from.span)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The keyword `word` itself. A name or a text that merely reads the same is not it.
+    pub fn is_keyword(&self, word: &str) -> bool {
+        self.kind == TokenKind::Keyword && self.text == word
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+// ================================================================
+// PUBLIC FUNCTIONS (The Logic)
+// ================================================================
+
+/// Splits one line of source into tokens and its comment. A bad quote comes back pointing at itself.
+pub fn tokenize(input: &str, line: usize) -> Result<Line, Diagnostic> {
+    let chars: Vec<char> = input.chars().collect();
     let mut tokens = Vec::new();
-    let mut current_token = String::new();
-    let mut inside_quote = false;
-
-    for c in input.chars(){
-        match c{
-            '"' =>{
-                inside_quote = !inside_quote;
-                current_token.push(c);
+    let mut current = String::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            push_token(&mut tokens, &mut current, line, start);
+            i += 1;
+            continue;
+        }
+
+        let starts_word = current.is_empty();
+        if starts_word {
+            start = i;
+        }
+
+        if c == constants::SYM_COMMENT || (starts_word && is_comment_word(&chars[i..])) {
+            push_token(&mut tokens, &mut current, line, start);
+            let skip = if c == constants::SYM_COMMENT { 1 } else { constants::KW_COMMENT.len() };
+            let comment: String = chars[i + skip..].iter().collect();
+            return Ok(Line { tokens, comment: Some(comment.trim().to_string()) });
+        }
+
+        if c == '"' || c == '\'' {
+            let end = scan_quote(&chars, i).map_err(|(error, span)| {
+                Diagnostic::error(&error).at(Some(Span { line, ..span }))
+            })?;
+            current.extend(&chars[i..=end]);
+            i = end + 1;
+            continue;
+        }
+
+        current.push(c);
+        i += 1;
+    }
+
+    push_token(&mut tokens, &mut current, line, start);
+    Ok(Line { tokens, comment: None })
+}
+
+/// Where the quote opened at `open` closes, skipping escaped quotes. Bad escapes and open quotes are errors.
+pub fn closing_quote(chars: &[char], open: usize) -> Result<usize, MommyLangError> {
+    scan_quote(chars, open).map_err(|(error, _)| error)
+}
+
+/// The pieces of a token, each with its own kind and column: "fib(n-1)" is "fib", "(", "n", "-", "1", ")".
+/// A text, a char, a name, a keyword or a plain number is one piece already. A signed number is split,
+/// "-2" is a minus in front of a 2, so the parser does not have to tell "x -2" from "x - 2".
+pub fn split(token: &Token) -> Result<Vec<Token>, MommyLangError> {
+    let atomic = match token.kind {
+        TokenKind::Text | TokenKind::Char | TokenKind::Identifier | TokenKind::Keyword | TokenKind::Punctuation => true,
+        TokenKind::Number => !token.text.starts_with('-'),
+        TokenKind::Operator | TokenKind::Compound => false,
+    };
+    if atomic {
+        return Ok(vec![token.clone()]);
+    }
+
+    let chars: Vec<char> = token.text.chars().collect();
+    let mut pieces = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+
+        if c.is_whitespace() { // A derived token can still hold the spaces of what it was put back together from
+            i += 1;
+            continue;
+        }
+
+        let kind = if c.is_ascii_digit() || c == constants::SYM_DOT {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == constants::SYM_DOT) {
+                i += 1;
+            }
+            i = exponent_end(&chars, i).unwrap_or(i); // "1e5", "2.5E-3"
+            if chars[start..i].iter().collect::<String>().parse::<f64>().is_err() {
+                return Err(MommyLangError::NotANumber);
+            }
+            TokenKind::Number
+        } else if c.is_alphabetic() || c == '_' {
+            // "ann.grade" is one name, the dot reaches into a record
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' ||
+                (chars[i] == constants::SYM_DOT && chars.get(i + 1).is_some_and(|next| next.is_alphabetic()))) {
+                i += 1;
             }
+            let word: String = chars[start..i].iter().collect();
+            if is_keyword(&word) { TokenKind::Keyword } else { TokenKind::Identifier }
+        } else if c == '"' || c == '\'' { // "fib("a")" and "x=='a'", quotes glued to the rest
+            i = closing_quote(&chars, i)? + 1;
+            if c == '"' { TokenKind::Text } else { TokenKind::Char }
+        } else if matches!(c, '(' | ')' | ',') {
+            i += 1;
+            TokenKind::Punctuation
+        } else {
+            i += match (c, chars.get(i + 1)) {
+                ('=' | '!' | '<' | '>', Some('=')) | ('&', Some('&')) | ('|', Some('|')) => 2,
+                ('!' | '<' | '>' | '+' | '-' | '*' | '/' | '%', _) => 1,
+                ('=' | '&' | '|', _) => return Err(MommyLangError::MalformedOperator), // "=", "=<", "&", "|"
+                _ => return Err(MommyLangError::SyntaxError),
+            };
+            TokenKind::Operator
+        };
+
+        let text: String = chars[start..i].iter().collect();
+        let span = Span { column: token.span.column + start, length: i - start, ..token.span };
+        pieces.push(Token { kind, text, span });
+    }
+    Ok(pieces)
+}
+
+/// The letters between the quotes of a text or char as the program sees them, "a\"b\n" is a, ", b and a newline.
+/// Only escapes the lexer let through can be here, anything else stays as it was written.
+pub fn unescape(inner: &str) -> String {
+    let mut letters = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != constants::SYM_BACKSLASH {
+            letters.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(escaped) => letters.push(escape_value(escaped).unwrap_or(escaped)),
+            None => letters.push(c),
+        }
+    }
+    letters
+}
+
+/// The tokens as one string, a space between each. For values read as a whole and for messages.
+pub fn join(tokens: &[Token]) -> String {
+    tokens.iter().map(Token::as_str).collect::<Vec<_>>().join(constants::SYM_WHITESPACE)
+}
+
+/// A value that is nothing but one string literal, "hello".
+pub fn is_text_literal(tokens: &[Token]) -> bool {
+    matches!(tokens, [token] if token.kind == TokenKind::Text)
+}
+
+pub fn classify(text: &str) -> TokenKind {
+    let chars: Vec<char> = text.chars().collect();
+
+    match chars.first() {
+        None => TokenKind::Compound,
+        Some('"') if closing_quote(&chars, 0).is_ok_and(|end| end + 1 == chars.len()) => TokenKind::Text,
+        Some('\'') if closing_quote(&chars, 0).is_ok_and(|end| end + 1 == chars.len()) => TokenKind::Char,
+        _ if is_number(text) => TokenKind::Number,
+        _ if is_keyword(text) => TokenKind::Keyword,
+        _ if is_identifier(text) => TokenKind::Identifier,
+        Some('(' | ')' | ',') if chars.len() == 1 => TokenKind::Punctuation,
+        _ if chars.iter().all(|c| OPERATOR_CHARS.contains(*c)) => TokenKind::Operator,
+        _ => TokenKind::Compound,
+    }
+}
 
-            ' ' | '\t' | '\r' | '\n' if !inside_quote => {
-                if !current_token.is_empty(){
-                    tokens.push(current_token.clone());
-                    current_token.clear();
+// ================================================================
+// HELPERS
+// ================================================================
+
+/// Like `closing_quote`, but also says where the problem is: the bad escape, or everything from the open quote on.
+/// The span's line is left for the caller.
+fn scan_quote(chars: &[char], open: usize) -> Result<usize, (MommyLangError, Span)> {
+    let quote = chars[open];
+    let unclosed = Span { line: 0, column: open + 1, length: chars.len() - open };
+    let mut i = open + 1;
+
+    while i < chars.len() {
+        match chars[i] {
+            constants::SYM_BACKSLASH => {
+                let escaped = chars.get(i + 1).ok_or((MommyLangError::UnclosedQuote, unclosed))?;
+                if escape_value(*escaped).is_none() {
+                    return Err((MommyLangError::UnknownEscape, Span { line: 0, column: i + 1, length: 2 }));
                 }
+                i += 2;
             }
+            c if c == quote => return Ok(i),
+            _ => i += 1,
+        }
+    }
+    Err((MommyLangError::UnclosedQuote, unclosed))
+}
 
-            _ => {
-                current_token.push(c);
-            }
+/// The letter an escape stands for, None for anything that is not in ESCAPES.
+fn escape_value(escaped: char) -> Option<char> {
+    match escaped {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        '0' => Some('\0'),
+        c if ESCAPES.contains(&c) => Some(c),
+        _ => None,
+    }
+}
+
+/// Where "e5", "E-3" or "e+2" right after a number ends. None when no exponent follows.
+fn exponent_end(chars: &[char], at: usize) -> Option<usize> {
+    if !matches!(chars.get(at), Some('e' | 'E')) {
+        return None;
+    }
+    let mut i = at + 1;
+    if matches!(chars.get(i), Some('+' | '-')) {
+        i += 1;
+    }
+    let digits = chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+    (digits > 0).then_some(i + digits)
+}
+
+fn push_token(tokens: &mut Vec<Token>, current: &mut String, line: usize, start: usize) {
+    if current.is_empty() {
+        return;
+    }
+    let span = Span { line, column: start + 1, length: current.chars().count() };
+    tokens.push(Token::new(current, span));
+    current.clear();
+}
+
+/// "psst" on its own, so "psstx" is still a name.
+fn is_comment_word(rest: &[char]) -> bool {
+    let word: Vec<char> = constants::KW_COMMENT.chars().collect();
+    rest.starts_with(&word) && rest.get(word.len()).is_none_or(|c| c.is_whitespace())
+}
+
+/// "10", "-2.5", "1e3". Rust would also take "inf" and "NaN", C would not.
+fn is_number(text: &str) -> bool {
+    text.chars().any(|c| c.is_ascii_digit()) &&
+        text.chars().all(|c| c.is_ascii_digit() || matches!(c, constants::SYM_DOT | '-' | '+' | 'e' | 'E')) &&
+        !text.starts_with(['+', 'e', 'E']) &&
+        text.parse::<f64>().is_ok()
+}
+
+fn is_keyword(text: &str) -> bool {
+    !matches!(MommyLangSyntax::from_word(text), MommyLangSyntax::Unknown) || CONNECTOR_WORDS.contains(&text)
+}
+
+/// "age", "_tmp", "ann.grade"
+fn is_identifier(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_alphabetic() || c == '_') &&
+        text.chars().all(|c| c.is_alphanumeric() || c == '_' || c == constants::SYM_DOT)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_of(input: &str, line: usize) -> Line {
+        match tokenize(input, line) {
+            Ok(line) => line,
+            Err(diagnostic) => panic!("{} did not tokenize: {}", input, diagnostic.message),
         }
     }
 
-    if !current_token.is_empty(){
-        tokens.push(current_token);
+    /// The code of the complaint and where it points.
+    fn complaint(input: &str) -> Option<(&'static str, Option<Span>)> {
+        tokenize(input, 1).err().map(|diagnostic| (diagnostic.code, diagnostic.span))
+    }
+
+    fn texts(line: &Line) -> Vec<&str> {
+        line.tokens.iter().map(Token::as_str).collect()
+    }
+
+    fn words(input: &str) -> (Vec<String>, Option<String>) {
+        let line = line_of(input, 1);
+        (line.tokens.iter().map(|token| token.text.clone()).collect(), line.comment)
+    }
+
+    #[test]
+    fn comments_end_the_code_but_not_inside_quotes() {
+        assert_eq!(words("say x # the total"), (vec!["say".to_string(), "x".to_string()], Some("the total".to_string())));
+        assert_eq!(words("say x psst the total"), (vec!["say".to_string(), "x".to_string()], Some("the total".to_string())));
+        assert_eq!(words("psst"), (Vec::new(), Some(String::new())));
+        assert_eq!(words("say \"#1 psst\"").1, None);
+        assert_eq!(words("mayihave '#' in c as char").1, None);
+        assert_eq!(words("say psstx"), (vec!["say".to_string(), "psstx".to_string()], None));
+    }
+
+    #[test]
+    fn unescape_gives_one_letter_per_escape() {
+        assert_eq!(unescape("a\\\"b\\n"), "a\"b\n");
+        assert_eq!(unescape("\\t\\r\\0\\\\\\'"), "\t\r\0\\'");
+        assert_eq!(unescape("plain"), "plain");
     }
 
-    tokens
+    #[test]
+    fn comment_marks_inside_quotes_are_not_comments() {
+        let line = line_of("say \"#1 psst\" # real", 1);
+        assert_eq!(texts(&line), ["say", "\"#1 psst\""]);
+        assert_eq!(line.comment.as_deref(), Some("real"));
 
-}
\ No newline at end of file
+        let line = line_of("mayihave '#' in c as char psst also real", 1);
+        assert_eq!(texts(&line), ["mayihave", "'#'", "in", "c", "as", "char"]);
+        assert_eq!(line.comment.as_deref(), Some("also real"));
+    }
+
+    #[test]
+    fn tokens_know_their_kind_and_place() {
+        let line = line_of("  say \"a b\\n\" 'x' -2.5 fib(n) ==", 4);
+        let kinds: Vec<TokenKind> = line.tokens.iter().map(|token| token.kind).collect();
+        assert_eq!(kinds, vec![TokenKind::Keyword, TokenKind::Text, TokenKind::Char, TokenKind::Number,
+            TokenKind::Compound, TokenKind::Operator]);
+        assert_eq!(line.tokens[1].text, "\"a b\\n\"");
+        assert_eq!(line.tokens[1].span, Span { line: 4, column: 7, length: 7 });
+    }
+
+    #[test]
+    fn only_known_escapes_and_closed_quotes() {
+        assert_eq!(complaint("say \"a\\q\""),
+            Some((MommyLangError::UnknownEscape.code(), Some(Span { line: 1, column: 7, length: 2 }))));
+        assert_eq!(complaint("say \"open"),
+            Some((MommyLangError::UnclosedQuote.code(), Some(Span { line: 1, column: 5, length: 5 }))));
+        assert_eq!(complaint("say 'x").map(|(code, _)| code), Some(MommyLangError::UnclosedQuote.code()));
+        assert_eq!(complaint("say \"tab\\t quote\\\" done\""), None);
+    }
+
+    #[test]
+    fn every_escape_is_kept_as_written() {
+        for escaped in ESCAPES {
+            let text = format!("\"a\\{}b\"", escaped);
+            let line = line_of(&format!("say {}", text), 1);
+            assert_eq!(texts(&line), ["say", text.as_str()]);
+            assert_eq!(line.tokens[1].kind, TokenKind::Text);
+
+            let char_literal = format!("'\\{}'", escaped);
+            assert_eq!(line_of(&char_literal, 1).tokens[0].kind, TokenKind::Char, "{}", char_literal);
+        }
+    }
+
+    #[test]
+    fn columns_count_leading_whitespace() {
+        let line = line_of("    say  total", 1);
+        assert_eq!(line.tokens[0].span, Span { line: 1, column: 5, length: 3 });
+        assert_eq!(line.tokens[1].span, Span { line: 1, column: 10, length: 5 });
+
+        let line = line_of("\tsay \"hé\"", 1);
+        assert_eq!(line.tokens[1].span, Span { line: 1, column: 6, length: 4 });
+    }
+
+    #[test]
+    fn split_keeps_columns_and_kinds() {
+        let line = line_of("  replace x with fib(n-1)", 1);
+        let pieces = split(&line.tokens[3]).unwrap();
+        let found: Vec<(&str, TokenKind, usize)> = pieces.iter().map(|p| (p.as_str(), p.kind, p.span.column)).collect();
+        assert_eq!(found, [
+            ("fib", TokenKind::Identifier, 18),
+            ("(", TokenKind::Punctuation, 21),
+            ("n", TokenKind::Identifier, 22),
+            ("-", TokenKind::Operator, 23),
+            ("1", TokenKind::Number, 24),
+            (")", TokenKind::Punctuation, 25),
+        ]);
+        assert_eq!(split(&line_of("a=b", 1).tokens[0]), Err(MommyLangError::MalformedOperator));
+    }
+}
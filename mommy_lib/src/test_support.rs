@@ -0,0 +1,52 @@
+//! Shared by the unit tests: a line of mommylang in, what the compiler works with out.
+
+use crate::declaration;
+use crate::io;
+use crate::lang_syntax::MommyLangSyntax;
+use crate::lifetime;
+use crate::record;
+use crate::responses::MommyLangError;
+use crate::symbol_table::SymbolTable;
+use crate::syntax_lexer::{self, Token};
+use crate::text;
+use crate::type_checker;
+
+/// The tokens of one line, the way the compiler sees them.
+pub fn tokens(line: &str) -> Vec<Token> {
+    match syntax_lexer::tokenize(line, 0) {
+        Ok(line) => line.tokens,
+        Err(diagnostic) => panic!("{} did not tokenize: {}", line, diagnostic.message),
+    }
+}
+
+/// One statement, type checked and turned into C like the compiler does it.
+pub fn run(line: &str, symbols: &mut SymbolTable) -> Result<String, MommyLangError> {
+    let tokens = tokens(line);
+    let command = MommyLangSyntax::from_word(tokens[0].as_str());
+    type_checker::check_statement(&command, &tokens, symbols)?;
+    lifetime::check_statement(&command, &tokens, symbols)?;
+    match command {
+        MommyLangSyntax::Declaration => declaration::create_variable(&tokens, symbols),
+        MommyLangSyntax::Constant => declaration::create_constant(&tokens, symbols),
+        MommyLangSyntax::Array => declaration::create_array(&tokens, symbols),
+        MommyLangSyntax::Malloc => declaration::allocate_heap(&tokens, symbols),
+        MommyLangSyntax::MallocResize => declaration::resize_heap(&tokens, symbols),
+        MommyLangSyntax::FreeMalloc => declaration::deallocate_heap(&tokens, symbols),
+        MommyLangSyntax::Assignment => declaration::replace(&tokens, symbols),
+        MommyLangSyntax::IO => io::say(&tokens, symbols),
+        MommyLangSyntax::Record => record::define(&tokens, symbols),
+        MommyLangSyntax::TextLength => text::measure(&tokens, symbols),
+        _ => panic!("the tests do not run {}", line),
+    }
+}
+
+/// A symbol table with every line in `lines` already declared.
+pub fn declared(lines: &[&str]) -> SymbolTable {
+    let mut symbols = SymbolTable::new();
+    for line in lines {
+        if let Err(error) = run(line, &mut symbols) {
+            panic!("{} was not declared: {}", line, error);
+        }
+    }
+    symbols
+}
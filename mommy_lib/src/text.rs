@@ -0,0 +1,193 @@
+//!
+//!
+//! This is the text crate of mommylang.
+//!
+//! Language Syntax:
+//! - Declaration: "mayihave "hello" in greeting as text"
+//! - Join: "join " world" onto greeting"
+//! - Length: "measure greeting in size" (a group or an ibegyou block gives its number of slots)
+//! - Slice: "slice greeting from 0 to 3 in part" (to is inclusive, like loops)
+//! - Compare: "ask if greeting == "hello"", "<" and ">" go by alphabetical order
+//! - To and from ascii arrays: "turn greeting into letters", "turn letters into greeting"
+//!
+//! Note:
+//! 1. Unlike String, a text owns its own copy on the heap. Replacing, joining or slicing frees the
+//!    old copy, so there is no fixed buffer to overflow and no literal to scribble on.
+//! 2. "listen" into a text reads the whole line, however long it is.
+//! 3. A text declared inside a block is freed when the block ends ("done", the next "or" or "when"), or when
+//!    "skip" or "satisfied" leaves it early. A loop does not leave a copy behind every round.
+//!    Texts at the top of the program live until it ends. A chore frees its texts before every "giveback"
+//!    and at its "done", only a text that is given back is left to the caller.
+//! 4. The C helpers live in the "words" package. It is added on its own when a program uses text.
+//!
+//!
+use crate::constants;
+use crate::declaration;
+use crate::expression::{self, ValueKind};
+use crate::lang_enums::SymbolKind;
+use crate::responses::MommyLangError;
+use crate::symbol_table::{Symbol, SymbolTable};
+use crate::validate_syntax;
+use crate::syntax_lexer::Token;
+
+// ================================================================
+// PUBLIC FUNCTIONS (The Logic)
+// ================================================================
+
+pub fn declare(name: &str, value: &str) -> String {
+    format!("char* {} = _mommy_text_new({});", name, value)
+}
+
+pub fn assign(name: &str, value: &str) -> String {
+    format!("_mommy_text_set(&{}, {});", name, value)
+}
+
+/// Frees the texts of a room that is closing, or being jumped out of. Goes in front of the closing C.
+pub fn release(names: &[String]) -> String {
+    names.iter().map(|name| format!("free({}); ", name)).collect()
+}
+
+pub fn listen(name: &str) -> String {
+    format!("_mommy_text_read(&{});", name)
+}
+
+pub fn join(
+    tokens: &[Token],
+    symbols: &SymbolTable
+) -> Result<String, MommyLangError> {
+    // Syntax: join <VALUE> onto <TARGET>
+
+    if tokens.len() < constants::ARGS_MIN_TEXT {
+        return Err(MommyLangError::MissingArguments);
+    }
+
+    let onto_index = tokens.iter().position(|t| t.is_keyword(constants::KW_ONTO))
+        .ok_or(MommyLangError::SyntaxError)?;
+    if onto_index < 2 || onto_index + 2 != tokens.len() {
+        return Err(MommyLangError::SyntaxError);
+    }
+
+    let target = tokens[onto_index + 1].as_str();
+    ensure_managed_target(target, symbols)?;
+    let value = text_value(&tokens[1..onto_index], symbols)?;
+
+    Ok(format!("_mommy_text_append(&{}, {});", target, value))
+}
+
+pub fn measure(
+    tokens: &[Token],
+    symbols: &SymbolTable
+) -> Result<String, MommyLangError> {
+    // Syntax: measure <TEXT> in <TARGET>
+
+    if tokens.len() < constants::ARGS_MIN_TEXT {
+        return Err(MommyLangError::MissingArguments);
+    }
+
+    let in_index = tokens.iter().position(|t| t.is_keyword(constants::KW_IN))
+        .ok_or(MommyLangError::SyntaxError)?;
+    if in_index < 2 || in_index + 2 != tokens.len() {
+        return Err(MommyLangError::SyntaxError);
+    }
+
+    let target = tokens[in_index + 1].as_str();
+    validate_syntax::ensure_mutable(symbols.lookup(target)?)?;
+
+    if let [source] = &tokens[1..in_index]
+        && let Some(length) = symbols.get(source.as_str()).and_then(declaration::c_length) {
+        return Ok(format!("{} = {};", target, length));
+    }
+
+    let value = text_value(&tokens[1..in_index], symbols)?;
+
+    Ok(format!("{} = _mommy_text_length({});", target, value))
+}
+
+pub fn slice(
+    tokens: &[Token],
+    symbols: &SymbolTable
+) -> Result<String, MommyLangError> {
+    // Syntax: slice <TEXT> from <START> to <END> in <TARGET>
+
+    let (from_index, to_index, in_index) = slice_positions(tokens)?;
+
+    let target = tokens[in_index + 1].as_str();
+    ensure_managed_target(target, symbols)?;
+
+    let source = text_value(&tokens[1..from_index], symbols)?;
+    let start = expression::parse_operand(&tokens[from_index + 1..to_index], symbols)?;
+    let end = expression::parse_operand(&tokens[to_index + 1..in_index], symbols)?;
+
+    Ok(format!("_mommy_text_slice(&{}, {}, {}, {});", target, source, start, end))
+}
+
+/// "turn" between a text and an ascii array, in either direction.
+pub fn convert(
+    value_tokens: &[Token],
+    target: &str,
+    symbols: &SymbolTable
+) -> Result<String, MommyLangError> {
+    let symbol = symbols.lookup(target)?;
+
+    // ascii array -> text
+    if is_managed(symbol) {
+        let [source] = value_tokens else { return Err(MommyLangError::TypeMismatch) };
+        let array = symbols.lookup(source.as_str())?;
+        let size = ascii_size(array).ok_or(MommyLangError::TypeMismatch)?;
+        return Ok(format!("_mommy_text_from_ascii(&{}, {}, {});", target, source, size));
+    }
+
+    // text -> ascii array
+    let size = ascii_size(symbol).ok_or(MommyLangError::TypeMismatch)?;
+    let value = text_value(value_tokens, symbols)?;
+    Ok(format!("_mommy_text_to_ascii({}, {}, {});", value, target, size))
+}
+
+pub fn is_managed(symbol: &Symbol) -> bool {
+    symbol.kind == SymbolKind::Scalar && symbol.data_type == constants::TYPE_TEXT
+}
+
+/// The size of an ascii array or heap block, the only thing a text can turn into.
+pub fn ascii_size(symbol: &Symbol) -> Option<&str> {
+    if symbol.is_indexable() && !symbol.is_grid() && symbol.data_type == constants::TYPE_ASCII {
+        return symbol.size.as_deref();
+    }
+    None
+}
+
+/// Where "from", "to" and "in" sit in a slice statement.
+pub fn slice_positions(tokens: &[Token]) -> Result<(usize, usize, usize), MommyLangError> {
+    let find = |keyword: &str, after: usize| {
+        tokens.iter().skip(after).position(|t| t.is_keyword(keyword)).map(|idx| idx + after)
+    };
+
+    let from_index = find(constants::KW_FROM, 0).ok_or(MommyLangError::SyntaxError)?;
+    let to_index = find(constants::KW_TO, from_index).ok_or(MommyLangError::SyntaxError)?;
+    let in_index = find(constants::KW_IN, to_index).ok_or(MommyLangError::SyntaxError)?;
+
+    if from_index < 2 || to_index == from_index + 1 || in_index == to_index + 1 || in_index + 2 != tokens.len() {
+        return Err(MommyLangError::SyntaxError);
+    }
+    Ok((from_index, to_index, in_index))
+}
+
+// ================================================================
+// HELPERS
+// ================================================================
+
+fn ensure_managed_target(name: &str, symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    let symbol = symbols.lookup(name)?;
+    validate_syntax::ensure_mutable(symbol)?;
+    if !is_managed(symbol) { // A String points at a literal, we cannot grow it
+        return Err(MommyLangError::TypeMismatch);
+    }
+    Ok(())
+}
+
+fn text_value(tokens: &[Token], symbols: &SymbolTable) -> Result<String, MommyLangError> {
+    let value = expression::parse_value(tokens, symbols)?;
+    if expression::kind_of(&value, symbols)? != ValueKind::Text {
+        return Err(MommyLangError::TypeMismatch);
+    }
+    Ok(value.to_string())
+}
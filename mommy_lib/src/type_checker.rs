@@ -0,0 +1,682 @@
+//!
+//!
+//! This is the type checker crate of mommylang.
+//!
+//! Every statement goes through here before any C is written for it.
+//!
+//! Checked:
+//! - Declarations: "mayihave 3.7 in count as int" (literal vs declared type)
+//! - Reassignment: "replace count with price" (variable to variable)
+//! - Pointers: "replace p with x address" and "inside", against the pointee type
+//! - Arrays: slot writes and reads against the element type, and the index must be an int
+//! - Array initializers: "group 3 in scores as int with 1, 2.5" checks every value against the element type
+//! - Math: "add count with 0.5" on an int, "mod" on a float
+//! - Input: "listen" only into something it can actually fill
+//! - Conversion: "turn" only between int, float and ascii, or between a text and an ascii array
+//! - Text: "join", "measure" and "slice" only work on texts, with int positions ("measure" also takes a group)
+//! - Heap: "ibegyoumore" needs an int size
+//! - Ranges: the "step" of "punishme row from 1 to 9 step 2" is added to an int counter, so it has to be an int
+//! - Choices: a member only fits its own choice, "mayihave red in today as mood" is rejected
+//! - Records: every value of "mayihave "Ann", 91.5 in ann as student" against its field, and a record
+//!   only takes the same record ("ann.grade" is checked like any float)
+//!
+//! Note:
+//! 1. int, char and ascii are all integers. A float can take an int, an int cannot take a float.
+//!    bool only takes bools (yes/no or a condition), and numbers cannot pretend to be one.
+//! 2. String and box can take null.
+//! 3. Statements with a broken shape are left alone here, the statement itself reports the syntax error.
+//! 4. A mismatch names both sides, e.g. "'count' (int) and '3.7' (float)".
+//!
+//!
+use crate::constants;
+use crate::declaration;
+use crate::expression::{self, Expr};
+use crate::lang_enums::SymbolKind;
+use crate::lang_syntax::MommyLangSyntax;
+use crate::record;
+use crate::responses::MommyLangError;
+use crate::symbol_table::{Symbol, SymbolTable};
+use crate::text;
+use crate::syntax_lexer::{self, Span, Token};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum DataType {
+    Int,       // int, char, ascii
+    Float,
+    Text,      // String / char*
+    Pointer,   // box and ibegyou blocks
+    Array,     // a whole group
+    Record,    // a whole record
+    Choice,    // a member of a "choices"
+    Logic,     // bool, or a comparison
+    Null,
+    Void,      // a chore that gives nothing back
+    Unchecked, // a type we do not know, so we do not judge it
+}
+
+impl DataType {
+    fn name(&self) -> &'static str {
+        match self {
+            DataType::Int => constants::TYPE_INT,
+            DataType::Float => constants::TYPE_FLOAT,
+            DataType::Text => constants::TYPE_STRING,
+            DataType::Pointer => constants::KW_BOX,
+            DataType::Array => constants::KW_ARRAY_DEC,
+            DataType::Record => constants::KW_RECORD,
+            DataType::Choice => constants::KW_CHOICES,
+            DataType::Logic => constants::TYPE_BOOL,
+            DataType::Null => constants::KW_NULL,
+            DataType::Void => constants::C_KW_VOID,
+            DataType::Unchecked => "unknown",
+        }
+    }
+}
+
+// ================================================================
+// PUBLIC FUNCTIONS (The Logic)
+// ================================================================
+
+pub fn check_statement(
+    command: &MommyLangSyntax,
+    tokens: &[Token],
+    symbols: &SymbolTable
+) -> Result<(), MommyLangError> {
+    match command {
+        MommyLangSyntax::Declaration | MommyLangSyntax::Constant => check_declaration(tokens, symbols),
+        MommyLangSyntax::Array => check_array_initializer(tokens, symbols),
+        MommyLangSyntax::Assignment => check_replace(tokens, symbols),
+        MommyLangSyntax::Math => check_math(tokens, symbols),
+        MommyLangSyntax::ReadInput => check_listen(tokens, symbols),
+        MommyLangSyntax::Conversion => check_conversion(tokens, symbols),
+        MommyLangSyntax::TextJoin => check_join(tokens, symbols),
+        MommyLangSyntax::TextLength => check_measure(tokens, symbols),
+        MommyLangSyntax::TextSlice => check_slice(tokens, symbols),
+        MommyLangSyntax::MallocResize => check_resize(tokens, symbols),
+        MommyLangSyntax::Pick => check_pick(tokens, symbols),
+        MommyLangSyntax::LoopStartBasic => check_range(tokens, symbols),
+        _ => Ok(()),
+    }
+}
+
+// ================================================================
+// STATEMENT CHECKS
+// ================================================================
+
+fn check_declaration(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: mayihave <VALUE> in <NAME> as <TYPE>
+    let Some(in_index) = tokens.iter().position(|t| t.is_keyword(constants::KW_IN)) else {
+        return Ok(());
+    };
+    let (Some(name), Some(raw_type)) = (tokens.get(in_index + 1).map(Token::as_str), tokens.get(in_index + 3).map(Token::as_str)) else {
+        return Ok(());
+    };
+    if in_index < 2 {
+        return Ok(());
+    }
+
+    let value_tokens = &tokens[1..in_index];
+    if let Some(record) = symbols.get(raw_type) && record.kind == SymbolKind::Record {
+        return check_record_initializer(name, record, value_tokens, symbols);
+    }
+
+    let value = value_type(value_tokens, symbols)?;
+    if symbols.get(raw_type).is_some_and(|choice| choice.kind == SymbolKind::Choice) {
+        return ensure_fits(name, DataType::Choice, &syntax_lexer::join(value_tokens), value) // Named after the choice, not "choices"
+            .map_err(|_| record_mismatch(name, raw_type, &syntax_lexer::join(value_tokens), value.name()))
+            .and_then(|_| ensure_same_named(name, raw_type, &syntax_lexer::join(value_tokens), symbols));
+    }
+    ensure_fits(name, declared_type(raw_type), &syntax_lexer::join(value_tokens), value)
+}
+
+fn check_record_initializer(name: &str, record: &Symbol, value_tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: mayihave null in <NAME> as <RECORD>, mayihave <OTHER> in ..., mayihave <VALUE>, <VALUE> in ...
+    let values = declaration::split_initializer(value_tokens);
+    if let [value] = values.as_slice() {
+        if value.is_keyword(constants::KW_NULL) {
+            return Ok(());
+        }
+        if let Some(other) = symbols.get(value.as_str()) && other.is_record() {
+            return ensure_same_named(name, &record.data_type, value.as_str(), symbols);
+        }
+    }
+
+    for ((field, field_type), value) in record.fields.iter().zip(&values).filter(|(_, v)| !v.text.is_empty()) {
+        let slot = format!("{}{}{}", name, constants::SYM_DOT, field);
+        let value_tokens = std::slice::from_ref(value);
+        ensure_fits(&slot, declared_type(field_type), value.as_str(), value_type(value_tokens, symbols)?)?;
+    }
+    Ok(())
+}
+
+fn check_array_initializer(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: group <SIZE> in <NAME> as <TYPE> with <VALUES>
+    if tokens.len() <= constants::IDX_ARR_INIT_VALUES || !tokens[constants::IDX_ARR_INIT_KEY_WITH].is_keyword(constants::KW_WITH) {
+        return Ok(());
+    }
+
+    let name = tokens[constants::IDX_DECL_NAME].as_str();
+    let raw_type = tokens[constants::IDX_DECL_TYPE].as_str();
+    let values = declaration::split_initializer(&tokens[constants::IDX_ARR_INIT_VALUES..]);
+    if declaration::initializer_letters(raw_type, &values).is_some() {
+        return Ok(());
+    }
+
+    for (index, value) in values.iter().enumerate().filter(|(_, v)| !v.text.is_empty()) {
+        let slot = format!("{} in {}", name, index);
+        let value_tokens = std::slice::from_ref(value);
+        ensure_fits(&slot, declared_type(raw_type), value.as_str(), value_type(value_tokens, symbols)?)?;
+    }
+    Ok(())
+}
+
+fn check_replace(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    if tokens.len() < constants::ARGS_MIN_ASSIGN {
+        return Ok(());
+    }
+
+    // Write: replace <ARRAY> in <INDEX> with <VALUE>
+    if tokens[constants::IDX_ARR_KEY_IN].is_keyword(constants::KW_IN) {
+        if tokens.len() < constants::ARGS_MIN_ARR_ASSIGN {
+            return Ok(());
+        }
+        let array = symbols.lookup(tokens[constants::IDX_ARR_NAME].as_str())?;
+        let index = tokens[constants::IDX_ARR_INDEX].as_str();
+        check_index(index, symbols)?;
+
+        let Some(element) = element_type(array, index)? else { return Ok(()) };
+        let slot = format!("{} in {}", tokens[constants::IDX_ARR_NAME], index);
+        let value = &tokens[constants::IDX_ARR_VALUE..];
+        ensure_fits(&slot, element, &syntax_lexer::join(value), value_type(value, symbols)?)?;
+        return ensure_same_named(&slot, &array.data_type, &syntax_lexer::join(value), symbols);
+    }
+
+    if !tokens[constants::IDX_ASSIGN_KEY_WITH].is_keyword(constants::KW_WITH) {
+        return Ok(());
+    }
+
+    let name = tokens[constants::IDX_ASSIGN_NAME].as_str();
+    let target = symbols.lookup(name)?;
+
+    // Read: replace <DEST> with <ARRAY> in <INDEX>
+    if tokens.len() >= constants::ARGS_MIN_ARR_ASSIGN && tokens[constants::IDX_ARR_KEY_WITH].is_keyword(constants::KW_IN) {
+        if tokens.len() > constants::ARGS_ARR_READ {
+            return Ok(()); // Anything after the index is a syntax error, reported by the statement
+        }
+        let array = symbols.lookup(tokens[constants::IDX_ARR_READ_SOURCE].as_str())?;
+        let index = tokens[constants::IDX_ARR_READ_INDEX].as_str();
+        check_index(index, symbols)?;
+
+        let Some(element) = element_type(array, index)? else { return Ok(()) };
+        let source = syntax_lexer::join(&tokens[constants::IDX_ARR_READ_SOURCE..]);
+        ensure_fits(name, symbol_type(target), &source, element)?;
+        if matches!(element, DataType::Record | DataType::Choice) && target.data_type != array.data_type {
+            return Err(record_mismatch(name, &target.data_type, &source, &array.data_type));
+        }
+        return Ok(());
+    }
+
+    let value_tokens = &tokens[constants::IDX_ASSIGN_VALUE..];
+    let last_token = &tokens[tokens.len() - 1];
+
+    // Address: replace <PTR> with <VAR> address
+    if last_token.is_keyword(constants::KW_ADDR) {
+        let value_name = tokens[constants::IDX_ASSIGN_VALUE].as_str();
+        let value = symbols.lookup(value_name)?;
+        let pointee = pointee_type(target);
+
+        // A float* cannot hold an int's address, so the types must be the same, not just fit
+        if pointee.is_none() || value.kind != SymbolKind::Scalar || pointee != Some(symbol_type(value)) {
+            return Err(mismatch(name, symbol_type(target), &syntax_lexer::join(value_tokens), symbol_type(value)));
+        }
+        return Ok(());
+    }
+
+    // Dereference: replace <PTR> with <VALUE> inside / replace <VAR> with <PTR> inside
+    if last_token.is_keyword(constants::KW_DEREF) {
+        let inner_tokens = &value_tokens[..value_tokens.len() - 1];
+        if let Some(pointee) = pointee_type(target) {
+            let slot = format!("{} {}", name, constants::KW_DEREF);
+            return ensure_fits(&slot, pointee, &syntax_lexer::join(inner_tokens), value_type(inner_tokens, symbols)?);
+        }
+
+        let pointer = symbols.lookup(tokens[constants::IDX_ASSIGN_VALUE].as_str())?;
+        let Some(pointee) = pointee_type(pointer) else {
+            return Err(mismatch(name, symbol_type(target), &syntax_lexer::join(inner_tokens), symbol_type(pointer)));
+        };
+        return ensure_fits(name, symbol_type(target), &syntax_lexer::join(value_tokens), pointee);
+    }
+
+    ensure_fits(name, symbol_type(target), &syntax_lexer::join(value_tokens), value_type(value_tokens, symbols)?)?;
+    ensure_same_named(name, &target.data_type, &syntax_lexer::join(value_tokens), symbols)
+}
+
+fn check_math(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: <OPERATION> <TARGET> with <VALUE>
+    if tokens.len() < constants::ARGS_MIN_MATH {
+        return Ok(());
+    }
+
+    let name = tokens[constants::IDX_MATH_TARGET].as_str();
+    let target = symbol_type(symbols.lookup(name)?);
+    let value_tokens = &tokens[constants::IDX_MATH_SOURCE..];
+    let value = value_type(value_tokens, symbols)?;
+
+    let is_division = tokens[0].is_keyword(constants::KW_DIVIDE) || tokens[0].is_keyword(constants::KW_MOD);
+    if is_division && expression::read_operand(value_tokens, symbols).is_ok_and(|divisor| divisor.is_literal_zero()) {
+        return Ok(()); // "divide x with 0.0" has a bigger problem than its type, the ALU says so
+    }
+
+    // "add yes with 1" is not a thing, and neither is adding to a whole record or a choice
+    let is_countable = |data_type| !matches!(data_type, DataType::Logic | DataType::Record | DataType::Choice);
+    if !is_countable(target) || !is_countable(value) {
+        return Err(mismatch(name, target, &syntax_lexer::join(value_tokens), value));
+    }
+
+    // Strings and other non-numbers get their own complaint from the ALU
+    if !is_numeric(target) || !is_numeric(value) {
+        return Ok(());
+    }
+
+    let is_mod = tokens[0].is_keyword(constants::KW_MOD);
+    if (is_mod && (target == DataType::Float || value == DataType::Float)) ||
+        (target == DataType::Int && value == DataType::Float) {
+        return Err(mismatch(name, target, &syntax_lexer::join(value_tokens), value));
+    }
+    Ok(())
+}
+
+fn check_range(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: punishme <NAME> from <START> to <END> [step <STEP>] [named <LABEL>]
+    if tokens.get(constants::IDX_LOOP_KEY_FROM).is_none_or(|t| !t.is_keyword(constants::KW_FROM)) {
+        return Ok(());
+    }
+    let end = tokens.iter().position(|t| t.is_keyword(constants::KW_NAMED)).unwrap_or(tokens.len());
+    let Some(to_index) = tokens[..end].iter().position(|t| t.is_keyword(constants::KW_TO)) else { return Ok(()) };
+    let step_index = tokens[..end].iter().position(|t| t.is_keyword(constants::KW_STEP));
+
+    // The counter is a C int, so the start, the end and the step all have to be whole
+    let mut parts = vec![&tokens[constants::IDX_LOOP_KEY_FROM + 1..to_index]];
+    match step_index {
+        Some(step_index) if step_index > to_index => {
+            parts.push(&tokens[to_index + 1..step_index]);
+            parts.push(&tokens[step_index + 1..end]);
+        }
+        _ => parts.push(&tokens[to_index + 1..end]),
+    }
+
+    for part in parts.into_iter().filter(|part| !part.is_empty()) {
+        let found = value_type(part, symbols)?;
+        if found == DataType::Float {
+            return Err(mismatch(tokens[constants::IDX_LOOP_COUNT].as_str(), DataType::Int, &syntax_lexer::join(part), found));
+        }
+    }
+    Ok(())
+}
+
+fn check_listen(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: listen <VAR> [upto <SIZE>]
+    let Some(name) = tokens.get(constants::IDX_IO_VALUE).map(Token::as_str) else { return Ok(()) };
+    let symbol = symbols.lookup(name)?;
+
+    let can_listen = match symbol.kind {
+        SymbolKind::Scalar => matches!(symbol.data_type.as_str(), constants::TYPE_INT | constants::TYPE_FLOAT | constants::TYPE_BOOL) || symbol.is_text(),
+        SymbolKind::Array | SymbolKind::Heap => symbol.data_type == constants::TYPE_ASCII || symbol.is_text(),
+        _ => false,
+    };
+
+    if (can_listen && !symbol.is_grid()) || declared_type(&symbol.data_type) == DataType::Unchecked {
+        return Ok(());
+    }
+    Err(mismatch(name, symbol_type(symbol), "typed input", DataType::Text))
+}
+
+fn check_conversion(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: turn <VALUE> into <TARGET>
+    let Some(into_index) = tokens.iter().position(|t| t.is_keyword(constants::KW_INTO)) else { return Ok(()) };
+    let Some(name) = tokens.get(into_index + 1).map(Token::as_str) else { return Ok(()) };
+    if into_index < 2 {
+        return Ok(());
+    }
+
+    let target_symbol = symbols.lookup(name)?;
+    let target = symbol_type(target_symbol);
+    let value_tokens = &tokens[1..into_index];
+    let value = value_type(value_tokens, symbols)?;
+
+    // A text and an ascii array trade places, nothing else does
+    if text::is_managed(target_symbol) {
+        let source = symbols.get(&syntax_lexer::join(value_tokens));
+        if source.is_some_and(|s| text::ascii_size(s).is_some()) {
+            return Ok(());
+        }
+        return Err(mismatch(name, target, &syntax_lexer::join(value_tokens), value));
+    }
+    if text::ascii_size(target_symbol).is_some() {
+        return ensure_fits(name, DataType::Text, &syntax_lexer::join(value_tokens), value);
+    }
+
+    // Only numbers change shape. A String or a whole group has nothing to cast.
+    if is_numeric(target) && is_numeric(value) {
+        return Ok(());
+    }
+    Err(mismatch(name, target, &syntax_lexer::join(value_tokens), value))
+}
+
+fn check_join(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: join <VALUE> onto <TARGET>
+    let Some(onto_index) = tokens.iter().position(|t| t.is_keyword(constants::KW_ONTO)) else { return Ok(()) };
+    let Some(name) = tokens.get(onto_index + 1).map(Token::as_str) else { return Ok(()) };
+    if onto_index < 2 {
+        return Ok(());
+    }
+
+    let value_tokens = &tokens[1..onto_index];
+    ensure_fits(name, DataType::Text, &syntax_lexer::join(value_tokens), value_type(value_tokens, symbols)?)
+}
+
+fn check_measure(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: measure <TEXT> in <TARGET>
+    let Some(in_index) = tokens.iter().position(|t| t.is_keyword(constants::KW_IN)) else { return Ok(()) };
+    let Some(name) = tokens.get(in_index + 1).map(Token::as_str) else { return Ok(()) };
+    if in_index < 2 {
+        return Ok(());
+    }
+
+    let value_tokens = &tokens[1..in_index];
+    let is_group = symbols.get(&syntax_lexer::join(value_tokens)).is_some_and(|symbol| symbol.is_indexable());
+    let value = if is_group { DataType::Text } else { value_type(value_tokens, symbols)? };
+    if value != DataType::Text && value != DataType::Unchecked { // Only words and groups have a length
+        return Err(mismatch(constants::KW_MEASURE, DataType::Text, &syntax_lexer::join(value_tokens), value));
+    }
+
+    let target = symbol_type(symbols.lookup(name)?);
+    ensure_fits(name, target, &syntax_lexer::join(value_tokens), DataType::Int)
+}
+
+fn check_slice(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: slice <TEXT> from <START> to <END> in <TARGET>
+    let Ok((from_index, to_index, in_index)) = text::slice_positions(tokens) else { return Ok(()) };
+
+    let source_tokens = &tokens[1..from_index];
+    let source = value_type(source_tokens, symbols)?;
+    if source != DataType::Text && source != DataType::Unchecked {
+        return Err(mismatch(constants::KW_SLICE, DataType::Text, &syntax_lexer::join(source_tokens), source));
+    }
+
+    for position in [&tokens[from_index + 1..to_index], &tokens[to_index + 1..in_index]] {
+        let position_type = value_type(position, symbols)?;
+        if position_type != DataType::Int && position_type != DataType::Unchecked {
+            return Err(mismatch("position", DataType::Int, &syntax_lexer::join(position), position_type));
+        }
+    }
+    Ok(())
+}
+
+fn check_resize(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: ibegyoumore <SIZE> in <NAME>
+    let Some(size) = tokens.get(constants::IDX_DECL_VALUE) else { return Ok(()) };
+    let size_type = value_type(std::slice::from_ref(size), symbols)?;
+    if size_type == DataType::Int || size_type == DataType::Unchecked {
+        return Ok(());
+    }
+    Err(mismatch("size", DataType::Int, size.as_str(), size_type))
+}
+
+fn check_pick(tokens: &[Token], symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    // Syntax: pick <VALUE>
+    let value_tokens = &tokens[constants::IDX_PICK_VALUE.min(tokens.len())..];
+    if value_tokens.is_empty() {
+        return Ok(());
+    }
+
+    // C can only switch on whole numbers, and a choice is one underneath
+    match value_type(value_tokens, symbols)? {
+        DataType::Int | DataType::Choice | DataType::Unchecked => Ok(()),
+        value => Err(mismatch(constants::KW_PICK, DataType::Int, &syntax_lexer::join(value_tokens), value)),
+    }
+}
+
+// ================================================================
+// TYPE HELPERS
+// ================================================================
+
+fn check_index(index: &str, symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    let (index, _) = record::split_field(index);
+    for part in declaration::split_index(index) { // "1,j" on a grid, every side on its own
+        let index_type = value_type(&[Token::new(part, Span::default())], symbols)?;
+        if index_type != DataType::Int && index_type != DataType::Unchecked {
+            return Err(mismatch("index", DataType::Int, part, index_type));
+        }
+    }
+    Ok(())
+}
+
+/// "'age' (int)", one side of a comparison that does not fit. Records and choices go by their own name.
+pub fn describe(expr: &Expr, symbols: &SymbolTable) -> String {
+    let named = match expr {
+        Expr::Variable(name) => symbols.get(name).filter(|symbol| symbol.is_record() || symbol.is_choice()),
+        _ => None,
+    };
+    match named {
+        Some(symbol) => format!("'{}' ({})", expr, symbol.data_type),
+        None => format!("'{}' ({})", expr, type_of(expr, symbols).unwrap_or(DataType::Unchecked).name()),
+    }
+}
+
+/// A float in C, so its zero check has to take a double. Anything else is checked as an int.
+pub fn is_float(expr: &Expr, symbols: &SymbolTable) -> bool {
+    type_of(expr, symbols) == Ok(DataType::Float)
+}
+
+/// The type of a value written after "with" or before "in", the same way declaration reads it.
+fn value_type(tokens: &[Token], symbols: &SymbolTable) -> Result<DataType, MommyLangError> {
+    let raw = syntax_lexer::join(tokens);
+    if syntax_lexer::is_text_literal(tokens) {
+        return Ok(DataType::Text);
+    }
+    if raw == constants::KW_NULL {
+        return Ok(DataType::Null);
+    }
+    if let Some(symbol) = symbols.get(&raw) && symbol.kind == SymbolKind::Array {
+        return Ok(DataType::Array); // A whole group is not a value, but we still want to name it
+    }
+
+    let expr = expression::read_value(tokens, symbols)?;
+    type_of(&expr, symbols)
+}
+
+fn type_of(expr: &Expr, symbols: &SymbolTable) -> Result<DataType, MommyLangError> {
+    match expr {
+        Expr::Number(n) if expression::is_float_literal(n) => Ok(DataType::Float),
+        Expr::Number(_) | Expr::Char(_) => Ok(DataType::Int),
+        Expr::Text(_) => Ok(DataType::Text),
+        Expr::Null => Ok(DataType::Null),
+        Expr::Bool(_) => Ok(DataType::Logic),
+        Expr::Variable(name) => Ok(symbol_type(symbols.lookup(name)?)),
+        Expr::Call(name, _) => match symbols.get(name) {
+            Some(chore) if chore.data_type == constants::C_KW_VOID => Ok(DataType::Void),
+            Some(chore) => Ok(declared_type(&chore.data_type)),
+            None => Err(MommyLangError::UndeclaredFunction),
+        },
+        Expr::Negate(inner) | Expr::Group(inner) | Expr::Guarded(inner, ..) => type_of(inner, symbols),
+        Expr::Not(_) => Ok(DataType::Logic),
+        Expr::TextCompare(..) => Ok(DataType::Logic),
+        Expr::Binary(_, op, _) if expression::is_comparison(op) || expression::is_logical(op) => Ok(DataType::Logic),
+        Expr::Binary(left, op, right) => {
+            let left_type = type_of(left, symbols)?;
+            let right_type = type_of(right, symbols)?;
+
+            if *op == constants::C_OP_MOD && (left_type == DataType::Float || right_type == DataType::Float) {
+                return Err(mismatch(&left.to_string(), left_type, &right.to_string(), right_type)); // C has no % on floats
+            }
+            match (left_type, right_type) {
+                (DataType::Unchecked, _) | (_, DataType::Unchecked) => Ok(DataType::Unchecked),
+                (DataType::Float, _) | (_, DataType::Float) => Ok(DataType::Float),
+                _ => Ok(DataType::Int),
+            }
+        }
+    }
+}
+
+fn declared_type(raw_type: &str) -> DataType {
+    match raw_type {
+        constants::TYPE_INT | constants::TYPE_ASCII | constants::C_KW_CHAR => DataType::Int,
+        constants::TYPE_FLOAT => DataType::Float,
+        constants::TYPE_BOOL => DataType::Logic,
+        constants::TYPE_STRING | constants::C_TYPE_CHAR_PTR | constants::TYPE_TEXT => DataType::Text,
+        t if t == constants::KW_BOX || t == constants::KW_POINTER => DataType::Pointer,
+        _ => DataType::Unchecked,
+    }
+}
+
+fn symbol_type(symbol: &Symbol) -> DataType {
+    match symbol.kind {
+        SymbolKind::Scalar if symbol.is_record() => DataType::Record,
+        SymbolKind::Scalar if symbol.is_choice() => DataType::Choice,
+        SymbolKind::Scalar => declared_type(&symbol.data_type),
+        SymbolKind::Pointer | SymbolKind::Heap => DataType::Pointer,
+        SymbolKind::Array => DataType::Array,
+        SymbolKind::Function | SymbolKind::Record | SymbolKind::Choice => DataType::Void,
+    }
+}
+
+/// What a single slot holds when you read "<NAME> in <INDEX>". "<NAME> in <INDEX>.<FIELD>" holds the field.
+fn element_type(symbol: &Symbol, index: &str) -> Result<Option<DataType>, MommyLangError> {
+    if let (_, Some(field)) = record::split_field(index) {
+        let field_type = symbol.field_type(field).ok_or(MommyLangError::UnknownField)?;
+        return Ok(Some(declared_type(field_type)));
+    }
+    if symbol.is_record() {
+        return Ok(Some(DataType::Record));
+    }
+    if symbol.is_choice() {
+        return Ok(Some(DataType::Choice));
+    }
+
+    Ok(match symbol.kind {
+        SymbolKind::Array | SymbolKind::Heap => Some(declared_type(&symbol.data_type)),
+        SymbolKind::Scalar if symbol.is_text() => Some(DataType::Int), // a single char
+        SymbolKind::Pointer => Some(DataType::Int),
+        _ => None,
+    })
+}
+
+/// What sits behind a pointer. "box" is always an int*, a heap block has its own type.
+fn pointee_type(symbol: &Symbol) -> Option<DataType> {
+    match symbol.kind {
+        SymbolKind::Pointer => Some(DataType::Int),
+        SymbolKind::Heap => Some(declared_type(&symbol.data_type)),
+        _ => None,
+    }
+}
+
+fn is_numeric(data_type: DataType) -> bool {
+    matches!(data_type, DataType::Int | DataType::Float)
+}
+
+fn ensure_fits(target: &str, target_type: DataType, value: &str, value_type: DataType) -> Result<(), MommyLangError> {
+    let fits = matches!(
+        (target_type, value_type),
+        (DataType::Unchecked, _) | (_, DataType::Unchecked) |
+        (DataType::Int, DataType::Int) |
+        (DataType::Float, DataType::Int | DataType::Float) |
+        (DataType::Logic, DataType::Logic) |
+        (DataType::Record, DataType::Record) |
+        (DataType::Choice, DataType::Choice) |
+        (DataType::Text, DataType::Text | DataType::Null) |
+        (DataType::Pointer, DataType::Pointer | DataType::Null)
+    );
+
+    if fits {
+        return Ok(());
+    }
+    Err(mismatch(target, target_type, value, value_type))
+}
+
+/// Records and choices only fit their own kind, a student is not a teacher and a mood is not a color.
+fn ensure_same_named(target: &str, target_type: &str, value: &str, symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    match symbols.get(value) {
+        Some(other) if (other.is_record() || other.is_choice()) && other.data_type != target_type =>
+            Err(record_mismatch(target, target_type, value, &other.data_type)),
+        _ => Ok(()),
+    }
+}
+
+fn record_mismatch(target: &str, target_type: &str, value: &str, value_type: &str) -> MommyLangError {
+    MommyLangError::TypeMismatchOn(format!("'{}' ({})", target, target_type), format!("'{}' ({})", value, value_type))
+}
+
+fn mismatch(target: &str, target_type: DataType, value: &str, value_type: DataType) -> MommyLangError {
+    MommyLangError::TypeMismatchOn(
+        format!("'{}' ({})", target, target_type.name()),
+        format!("'{}' ({})", value, value_type.name()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{declared, tokens};
+
+    fn check(line: &str, symbols: &SymbolTable) -> Result<(), MommyLangError> {
+        let tokens = tokens(line);
+        check_statement(&MommyLangSyntax::from_word(tokens[0].as_str()), &tokens, symbols)
+    }
+
+    fn on(target: &str, value: &str) -> Result<(), MommyLangError> {
+        Err(MommyLangError::TypeMismatchOn(target.to_string(), value.to_string()))
+    }
+
+    #[test]
+    fn float_does_not_fit_an_int() {
+        let symbols = SymbolTable::new();
+        assert_eq!(check("mayihave 3.7 in count as int", &symbols), on("'count' (int)", "'3.7' (float)"));
+        assert_eq!(check("mayihave 3 in price as float", &symbols), Ok(()));
+        assert_eq!(check("mayihave 'a' in count as int", &symbols), Ok(()));
+    }
+
+    #[test]
+    fn bool_only_takes_bools() {
+        let symbols = declared(&["mayihave 1 in count as int"]);
+        assert_eq!(check("mayihave 1 in ok as bool", &symbols), on("'ok' (bool)", "'1' (int)"));
+        assert_eq!(check("mayihave count > 1 and yes in ok as bool", &symbols), Ok(()));
+    }
+
+    #[test]
+    fn reassignment_checks_variable_against_variable() {
+        let symbols = declared(&["mayihave 1 in count as int", "mayihave 2.5 in price as float", "mayihave null in name as String"]);
+        assert_eq!(check("replace count with price", &symbols), on("'count' (int)", "'price' (float)"));
+        assert_eq!(check("replace price with count", &symbols), Ok(()));
+        assert_eq!(check("replace name with null", &symbols), Ok(()));
+        assert_eq!(check("replace count with name", &symbols), on("'count' (int)", "'name' (String)"));
+    }
+
+    #[test]
+    fn array_write_checks_the_whole_expression() {
+        let mut symbols = declared(&["mayihave 1 in a as int"]);
+        declaration::create_array(&tokens("group 3 in xs as int"), &mut symbols).unwrap();
+        assert_eq!(check("replace xs in 0 with a + 0.5", &symbols), on("'xs in 0' (int)", "'a + 0.5' (float)"));
+        assert_eq!(check("replace xs in 0 with a + 10", &symbols), Ok(()));
+    }
+
+    #[test]
+    fn math_and_ranges_stay_whole_on_ints() {
+        let symbols = declared(&["mayihave 1 in count as int", "mayihave 0.5 in half as float"]);
+        assert_eq!(check("add count with 0.5", &symbols), on("'count' (int)", "'0.5' (float)"));
+        assert_eq!(check("punishme i from 1 to 9 step 0.5", &symbols), on("'i' (int)", "'0.5' (float)"));
+        assert_eq!(check("punishme i from 1 to 9 step 2", &symbols), Ok(()));
+        assert_eq!(check("punishme x from 0.5 to 3.7", &symbols), on("'x' (int)", "'0.5' (float)"));
+        assert_eq!(check("punishme x from 0 to count + half", &symbols), on("'x' (int)", "'count + half' (float)"));
+        assert_eq!(check("punishme x from count to 3 step 1 named outer", &symbols), Ok(()));
+    }
+
+    #[test]
+    fn comparison_names_both_operands() {
+        let symbols = declared(&["mayihave 1 in count as int", "mayihave \"mommy\" in name as String"]);
+        let condition = tokens("count == name");
+        assert_eq!(
+            expression::compile_condition(&condition, &symbols),
+            Err(MommyLangError::TypeMismatchOn("'count' (int)".to_string(), "'name' (String)".to_string())),
+        );
+    }
+}
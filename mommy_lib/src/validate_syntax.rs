@@ -1,24 +1,30 @@
-use std::collections::HashMap;
 use crate::constants;
+use crate::lang_enums::SymbolKind;
 use crate::responses::MommyLangError;
+use crate::symbol_table::{Symbol, SymbolTable};
+use crate::syntax_lexer::Token;
 
 // ================================================================
 // VARIABLE VALIDATION (Symbol Table Operations)
 // ================================================================
 
-pub fn ensure_var_new(name: &str, symbols: &HashMap<String, String>) -> Result<(), MommyLangError> {
-    if symbols.contains_key(name) {
+pub fn ensure_var_new(name: &str, symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    if symbols.declared_here(name) {
         Err(MommyLangError::VariableAlreadyExists)
     } else {
         Ok(())
     }
 }
 
-pub fn ensure_var_exists(name: &str, symbols: &HashMap<String, String>) -> Result<(), MommyLangError> {
-    if !symbols.contains_key(name) {
-        Err(MommyLangError::UndeclaredVariable)
-    } else {
+pub fn ensure_var_exists(name: &str, symbols: &SymbolTable) -> Result<(), MommyLangError> {
+    symbols.lookup(name).map(|_| ())
+}
+
+pub fn ensure_mutable(symbol: &Symbol) -> Result<(), MommyLangError> {
+    if symbol.mutable {
         Ok(())
+    } else {
+        Err(MommyLangError::ImmutableVariable)
     }
 }
 
@@ -29,6 +35,15 @@ pub fn ensure_valid_name(name: &str) -> Result<(), MommyLangError> {
         constants::C_KW_VOID |
         constants::C_KW_CHAR |
         constants::KW_IF |
+        constants::KW_AND |
+        constants::KW_OR |
+        constants::KW_NOT |
+        constants::KW_NULL |
+        constants::KW_YES |
+        constants::KW_NO |
+        constants::KW_TRUE |
+        constants::KW_FALSE |
+        constants::C_KW_MAIN |
         constants::C_KW_WHILE => Err(MommyLangError::InvalidVariableName),
 
         _ => Ok(())
@@ -39,18 +54,23 @@ pub fn ensure_valid_name(name: &str) -> Result<(), MommyLangError> {
 // TYPE VALIDATION
 // ================================================================
 
-pub fn is_type_mismatch(is_type: &str) -> bool{
-    if !is_type.starts_with(constants::KW_ARRAY) &&
-       !is_type.starts_with(constants::KW_HEAP) &&
-        is_type != constants::TYPE_STRING &&
-        is_type != constants::KW_POINTER {
-        return true
+pub fn is_type_mismatch(symbol: &Symbol) -> bool{
+    let is_string = symbol.kind == SymbolKind::Scalar && symbol.data_type == constants::TYPE_STRING;
+    if symbol.is_indexable() || is_string || symbol.kind == SymbolKind::Pointer {
+        return false
     }
-    false
+    true
+}
+
+/// A type the language already has. Records and choices cannot take these names.
+pub fn is_builtin_type(name: &str) -> bool {
+    matches!(name, constants::TYPE_INT | constants::TYPE_FLOAT | constants::TYPE_ASCII | constants::TYPE_BOOL |
+        constants::TYPE_STRING | constants::TYPE_TEXT | constants::C_KW_CHAR) ||
+        name == constants::KW_BOX || name == constants::KW_POINTER
 }
 
-pub fn is_type_pointer(var_type: &str) -> bool{
-    if var_type == constants::KW_POINTER {
+pub fn is_type_pointer(symbol: &Symbol) -> bool{
+    if symbol.kind == SymbolKind::Pointer {
         return true
     }
     false
@@ -87,9 +107,9 @@ pub fn is_missing_say_args(tokens_len: usize) -> bool{
 // ARRAY VALIDATION
 // ================================================================
 
-pub fn is_invalid_array_tokens(tokens: &Vec<String>) -> bool{
-     if tokens[constants::IDX_DECL_KEY_IN] != constants::KW_IN ||
-        tokens[constants::IDX_DECL_KEY_AS] != constants::KW_AS {
+pub fn is_invalid_array_tokens(tokens: &[Token]) -> bool{
+     if !tokens[constants::IDX_DECL_KEY_IN].is_keyword(constants::KW_IN) ||
+        !tokens[constants::IDX_DECL_KEY_AS].is_keyword(constants::KW_AS) {
         return true;
     }
     false
@@ -102,23 +122,19 @@ pub fn is_invalid_array_size(size_str: &str) -> bool {
     }
 }
 
-pub fn is_accessed_index_invalid(var_type: &str, index: &str) -> bool{
-    // Check both stack arrays ("array:type:size") and heap allocations ("heap:type:size")
-    if var_type.starts_with(constants::KW_ARRAY) || var_type.starts_with(constants::KW_HEAP) {
-        let parts: Vec<&str> = var_type.split(constants::SYM_SPLITTER).collect();
-        if let Ok(max_size) = parts[2].parse::<usize>() {
-            if let Ok(idx_num) = index.parse::<usize>() {
-                if idx_num >= max_size {
-                   return true
-                }
-            }
-        }
+pub fn is_accessed_index_invalid(symbol: &Symbol, index: &str) -> bool{
+    // Check both stack arrays and heap allocations, as long as the size is a known number
+    if symbol.is_indexable()
+        && let Some(max_size) = symbol.fixed_size()
+        && let Ok(idx_num) = index.parse::<usize>()
+        && idx_num >= max_size {
+        return true
     }
     false
 }
 
-pub fn is_output_be_wildcard(array_type: &str, idx_num: usize, max_size: usize) -> bool {
-    if (array_type.starts_with(constants::KW_ARRAY) || array_type.starts_with(constants::KW_HEAP)) &&
+pub fn is_output_be_wildcard(symbol: &Symbol, idx_num: usize, max_size: usize) -> bool {
+    if symbol.is_indexable() &&
         idx_num >= max_size && max_size != constants::SIZE_UNKNOWN{
         return true
     }
@@ -142,22 +158,22 @@ pub fn is_kw_all(idx: &str) -> bool{
 // ASSIGNMENT VALIDATION (Replace/Pointer Operations)
 // ================================================================
 
-pub fn is_keyword_with_missing(tokens: &Vec<String>) -> bool{
-     if tokens[constants::IDX_ASSIGN_KEY_WITH] != constants::KW_WITH {
+pub fn is_keyword_with_missing(tokens: &[Token]) -> bool{
+     if !tokens[constants::IDX_ASSIGN_KEY_WITH].is_keyword(constants::KW_WITH) {
         return true
     }
     false
 }
 
-pub fn is_replace_pointer(last_token: &str) -> bool{
-    if last_token == constants::KW_ADDR{
+pub fn is_replace_pointer(last_token: &Token) -> bool{
+    if last_token.is_keyword(constants::KW_ADDR){
         return true
     }
     false
 }
 
-pub fn is_deref_assignment(last_token: &str) -> bool{
-    if last_token == constants::KW_DEREF{
+pub fn is_deref_assignment(last_token: &Token) -> bool{
+    if last_token.is_keyword(constants::KW_DEREF){
         return true
     }
     false
@@ -167,29 +183,9 @@ pub fn is_deref_assignment(last_token: &str) -> bool{
 // MEMORY MANAGEMENT VALIDATION (Heap Deallocation)
 // ================================================================
 
-pub fn is_invalid_dealloc_tokens(tokens: &Vec<String>) -> bool{
+pub fn is_invalid_dealloc_tokens(tokens: &[Token]) -> bool{
     if tokens.len() < constants::ARGS_MIN_LEN{
         return true
     }
     false
 }
-
-// ================================================================
-// SWAPPING VALUES
-// ================================================================
-
-pub fn select_inner_type(parts_len: usize, parts: &str) -> &str{
-     if parts_len > 1 {
-         return parts
-     }
-    constants::TYPE_STRING
-}
-
-pub fn select_max_size(parts_len: usize, parts: &str) -> usize {
-    if parts_len > 2 {
-        if let Ok(size) = parts.parse::<usize>() {
-            return size
-        }
-    }
-    0
-}
\ No newline at end of file
@@ -153,7 +153,7 @@ fn shell_attempt_command(input: &str, root_dir: &PathBuf, mommy_settings: &mut M
     }
 
     let command_str = &args[constants::IDX_STARTING_COMMAND];
-    let first_args = shell_commands::MommyShellCommands::from_str(command_str);
+    let first_args = shell_commands::MommyShellCommands::from_word(command_str);
 
     let passed_args = &args[1..];
 